@@ -0,0 +1,132 @@
+//! Heap allocation tracking, used to report the memory used by each part.
+//!
+//! Allocations are only tracked when [`CountingAllocator`] is the global
+//! allocator, which is the case when the `track-alloc` feature is enabled.
+//! The counters are kept per thread, so the memory used by a solver isn't
+//! mixed with the memory used by solvers running in other threads.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+#[cfg(feature = "track-alloc")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns whether allocations are being tracked.
+#[must_use]
+pub fn is_enabled() -> bool {
+    cfg!(feature = "track-alloc")
+}
+
+/// A global allocator that wraps the system allocator, counting the
+/// allocations and the heap size of each thread.
+pub struct CountingAllocator;
+
+struct Counters {
+    allocations: Cell<u64>,
+    /// The bytes allocated minus the bytes freed by the thread (which can be
+    /// negative if it frees memory allocated by other threads).
+    current: Cell<i64>,
+    /// The maximum value of `current` since the last [`Checkpoint`].
+    peak: Cell<i64>,
+}
+
+thread_local! {
+    static COUNTERS: Counters = const {
+        Counters {
+            allocations: Cell::new(0),
+            current: Cell::new(0),
+            peak: Cell::new(0),
+        }
+    };
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn record(allocated: usize, freed: usize, allocations: u64) {
+    // The counters are unavailable while the thread is being destroyed
+    COUNTERS
+        .try_with(|counters| {
+            let current = counters.current.get() + allocated as i64 - freed as i64;
+            counters.current.set(current);
+            counters.peak.set(counters.peak.get().max(current));
+            counters
+                .allocations
+                .set(counters.allocations.get() + allocations);
+        })
+        .ok();
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0, 1);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0, 1);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size(), 0);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size(), 1);
+        }
+        new_ptr
+    }
+}
+
+/// The memory used by a section of code (e.g. a part of a solver).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// The number of allocations (including reallocations).
+    pub allocations: u64,
+    /// The maximum heap size, in bytes, above the heap size at the start.
+    pub peak_bytes: u64,
+}
+
+/// The state of the allocation counters of the current thread, used to
+/// measure the memory used from that point on.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Checkpoint {
+    allocations: u64,
+    current: i64,
+}
+
+impl Checkpoint {
+    /// Creates a checkpoint, resetting the peak heap size of the current
+    /// thread.
+    #[must_use]
+    pub fn now() -> Self {
+        COUNTERS.with(|counters| {
+            let current = counters.current.get();
+            counters.peak.set(current);
+            Self {
+                allocations: counters.allocations.get(),
+                current,
+            }
+        })
+    }
+
+    /// Returns the memory used by the current thread since the checkpoint.
+    #[must_use]
+    pub fn memory_stats(&self) -> MemoryStats {
+        COUNTERS.with(|counters| MemoryStats {
+            allocations: counters.allocations.get() - self.allocations,
+            peak_bytes: (counters.peak.get() - self.current).max(0).unsigned_abs(),
+        })
+    }
+}
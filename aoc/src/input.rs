@@ -1,30 +1,69 @@
 use std::{
     borrow::Cow,
+    fmt::Display,
     hash::Hash,
+    io::Read,
     marker::PhantomData,
+    ops::Deref,
     os::unix::prelude::OsStrExt,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::{Arc, RwLock},
+    time::SystemTime,
 };
 
 use lazy_static::lazy_static;
 use regex::Regex;
 use rust_embed::RustEmbed;
 use rustc_hash::FxHashMap;
+use serde::de::DeserializeOwned;
 
 use crate::ProblemId;
 
+pub mod crypto;
+mod index;
+
+pub use index::{variant_matches, Index};
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Spec {
     pub id: ProblemId,
     pub variant: String,
 }
 
+impl Display for Spec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.id, self.variant)
+    }
+}
+
+impl FromStr for Spec {
+    type Err = Error;
+
+    /// Parses a spec in the format `yyyy.dd:variant` (the format used by
+    /// [`Spec`]'s `Display` implementation).
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidSpec { spec: s.to_owned() };
+        let (id, variant) = s.split_once(':').ok_or_else(invalid)?;
+        let (year, day) = id.split_once('.').ok_or_else(invalid)?;
+        Ok(Spec {
+            id: ProblemId {
+                year: year.parse().map_err(|_| invalid())?,
+                day: day.parse().map_err(|_| invalid())?,
+            },
+            variant: variant.to_owned(),
+        })
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("No input available for ({}/{}/{})", spec.id.year, spec.id.day, spec.variant)]
     NoInputAvailable { spec: Spec },
 
+    #[error("Invalid input spec \"{spec}\" (expected yyyy.dd:variant)")]
+    InvalidSpec { spec: String },
+
     #[error("Invalid input file name: {filename}")]
     InvalidFileName { filename: String },
 
@@ -34,6 +73,28 @@ pub enum Error {
     #[error("Invalid input file encoding: {0}")]
     InvalidFileEncodingInString(#[from] std::string::FromUtf8Error),
 
+    #[error(
+        "No key available to decrypt {filename} (set {} or create {})",
+        crypto::KEY_ENV_VAR,
+        crypto::Key::file_path().display()
+    )]
+    MissingKey { filename: String },
+
+    #[error("Invalid input key (expected 32 hex-encoded bytes)")]
+    InvalidKey,
+
+    #[error("Failed to decrypt {filename} (wrong key or corrupted file)")]
+    DecryptionFailed { filename: String },
+
+    #[error("Invalid front matter: {message}")]
+    InvalidFrontMatter { message: String },
+
+    #[error("Missing input parameter \"{name}\"")]
+    MissingParam { name: String },
+
+    #[error("Invalid input parameter \"{name}\": {message}")]
+    InvalidParam { name: String, message: String },
+
     #[error("IO error: {0}")]
     IOError(#[from] std::io::Error),
 
@@ -63,8 +124,10 @@ type Result<T> = std::result::Result<T, Error>;
 
 pub fn parse_input_filename(filename: &str) -> Result<Spec> {
     lazy_static! {
-        static ref FILENAME_RE: Regex =
-            Regex::new("^(?:.*/)?([0-9]+)/day([0-9]+)_([^\\.]+)\\.txt$").unwrap();
+        static ref FILENAME_RE: Regex = Regex::new(
+            "^(?:.*/)?([0-9]+)/day([0-9]+)_([^\\.]+)\\.txt(?:\\.gz|\\.zst)?(?:\\.enc)?$"
+        )
+        .unwrap();
     }
 
     let captures = FILENAME_RE
@@ -95,10 +158,203 @@ pub fn parse_input_filename(filename: &str) -> Result<Spec> {
     })
 }
 
+/// The contents of an input file: the puzzle input itself, the parameters
+/// given in its front matter and the expected answers, if known.
+///
+/// The front matter is an optional TOML header delimited by `---` lines at
+/// the start of the file. The `expected` table holds the expected answers and
+/// every other key is a parameter:
+///
+/// ```text
+/// ---
+/// target_y = 10
+///
+/// [expected]
+/// part1 = 26
+/// ---
+/// Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Input<'a> {
+    pub text: &'a str,
+    pub params: Params,
+    pub expected: [Option<String>; 2],
+}
+
+impl<'a> Input<'a> {
+    pub fn parse(content: &'a str) -> Result<Self> {
+        let mut lines = content.split_inclusive('\n');
+        let Some(first) = lines.next().filter(|line| line.trim_end() == "---") else {
+            return Ok(Input {
+                text: content,
+                ..Input::default()
+            });
+        };
+
+        let mut pos = first.len();
+        for line in lines {
+            if line.trim_end() == "---" {
+                let header = &content[first.len()..pos];
+                let mut params: toml::Table =
+                    header
+                        .parse()
+                        .map_err(|e: toml::de::Error| Error::InvalidFrontMatter {
+                            message: e.message().to_owned(),
+                        })?;
+                let expected = match params.remove("expected") {
+                    Some(toml::Value::Table(mut expected)) => {
+                        [expected.remove("part1"), expected.remove("part2")].map(|answer| {
+                            answer.map(|answer| match answer {
+                                toml::Value::String(answer) => answer,
+                                answer => answer.to_string(),
+                            })
+                        })
+                    }
+                    Some(_) => {
+                        return Err(Error::InvalidFrontMatter {
+                            message: "\"expected\" must be a table".to_owned(),
+                        })
+                    }
+                    None => [None, None],
+                };
+                return Ok(Input {
+                    text: &content[pos + line.len()..],
+                    params: Params(params),
+                    expected,
+                });
+            }
+            pos += line.len();
+        }
+        Err(Error::InvalidFrontMatter {
+            message: "missing closing \"---\"".to_owned(),
+        })
+    }
+}
+
+/// The parameters of an input (e.g. puzzle constants that differ between the
+/// examples and the real input).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params(toml::Table);
+
+impl Params {
+    /// Returns the parameter `name`, or `None` if the input doesn't define it.
+    pub fn get<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>> {
+        self.0
+            .get(name)
+            .map(|value| {
+                value
+                    .clone()
+                    .try_into()
+                    .map_err(|e: toml::de::Error| Error::InvalidParam {
+                        name: name.to_owned(),
+                        message: e.message().to_owned(),
+                    })
+            })
+            .transpose()
+    }
+}
+
+/// The compression format of an input file, given by its extension
+/// (`.txt.gz` or `.txt.zst`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn from_filename(filename: &str) -> Self {
+        match Path::new(filename).extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Self::Gzip,
+            Some("zst") => Self::Zstd,
+            _ => Self::None,
+        }
+    }
+
+    fn decode(self, data: Cow<'_, [u8]>) -> Result<Cow<'_, str>> {
+        let mut content = String::new();
+        Ok(match (self, data) {
+            (Self::None, Cow::Borrowed(data)) => Cow::Borrowed(std::str::from_utf8(data)?),
+            (Self::None, Cow::Owned(data)) => Cow::Owned(String::from_utf8(data)?),
+            (Self::Gzip, data) => {
+                flate2::read::GzDecoder::new(&*data).read_to_string(&mut content)?;
+                Cow::Owned(content)
+            }
+            (Self::Zstd, data) => {
+                zstd::Decoder::new(&*data)?.read_to_string(&mut content)?;
+                Cow::Owned(content)
+            }
+        })
+    }
+}
+
+/// Decodes the contents of an input file, decrypting and decompressing it
+/// according to its extensions.
+fn decode_file<'a>(filename: &str, data: Cow<'a, [u8]>) -> Result<Cow<'a, str>> {
+    match filename.strip_suffix(".enc") {
+        Some(inner) => {
+            let data = crypto::decrypt_file(filename, &data)?;
+            let content = Compression::from_filename(inner).decode(Cow::Owned(data))?;
+            Ok(Cow::Owned(content.into_owned()))
+        }
+        None => Compression::from_filename(filename).decode(data),
+    }
+}
+
+/// The contents of an input file, either borrowed from its source or shared
+/// with the source's cache (see [`FSSource`]), so that getting an input
+/// doesn't copy it.
+#[derive(Debug, Clone)]
+pub enum Content<'a> {
+    Borrowed(&'a str),
+    Shared(Arc<str>),
+}
+
+impl Deref for Content<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Content::Borrowed(content) => content,
+            Content::Shared(content) => content,
+        }
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Content<'a> {
+    fn from(content: Cow<'a, str>) -> Self {
+        match content {
+            Cow::Borrowed(content) => Content::Borrowed(content),
+            Cow::Owned(content) => Content::Shared(content.into()),
+        }
+    }
+}
+
 pub trait Source {
-    fn get(&self, key: &Spec) -> Result<Cow<'_, str>>;
-    // TODO: there are more efficient ways to do this
-    fn keys(&self) -> Vec<&Spec>;
+    fn get(&self, key: &Spec) -> Result<Content<'_>>;
+
+    /// Returns the specs of the available inputs, without duplicates (use an
+    /// [`Index`] to query them).
+    fn keys(&self) -> Box<dyn Iterator<Item = &Spec> + '_>;
+
+    fn contains(&self, key: &Spec) -> bool {
+        self.keys().any(|spec| spec == key)
+    }
+}
+
+impl<S: Source + ?Sized> Source for &S {
+    fn get(&self, key: &Spec) -> Result<Content<'_>> {
+        (**self).get(key)
+    }
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &Spec> + '_> {
+        (**self).keys()
+    }
+
+    fn contains(&self, key: &Spec) -> bool {
+        (**self).contains(key)
+    }
 }
 
 pub fn from_embedded<E: RustEmbed>() -> Result<EmbeddedSource<E>> {
@@ -123,7 +379,7 @@ pub fn chain<I1: Source, I2: Source>(first: I1, second: I2) -> ChainedSource<I1,
 }
 
 pub struct EmbeddedSource<E: RustEmbed> {
-    embed: PhantomData<*const E>,
+    embed: PhantomData<fn() -> E>,
     file_paths: FxHashMap<Spec, Cow<'static, str>>,
 }
 
@@ -152,42 +408,37 @@ impl<E: RustEmbed> EmbeddedSource<E> {
 }
 
 impl<E: RustEmbed> Source for EmbeddedSource<E> {
-    fn get(&self, spec: &Spec) -> Result<Cow<'_, str>> {
-        let file = self
-            .file_paths
-            .get(spec)
-            .and_then(|file_path| E::get(file_path))
-            .ok_or(Error::NoInputAvailable { spec: spec.clone() })?;
+    fn get(&self, spec: &Spec) -> Result<Content<'_>> {
+        let no_input = || Error::NoInputAvailable { spec: spec.clone() };
+        let file_path = self.file_paths.get(spec).ok_or_else(no_input)?;
+        let file = E::get(file_path).ok_or_else(no_input)?;
+        decode_file(file_path, file.data).map(Content::from)
+    }
 
-        let content = match file.data {
-            Cow::Borrowed(data) => Cow::Borrowed(std::str::from_utf8(data)?),
-            Cow::Owned(data) => Cow::Owned(String::from_utf8(data)?),
-        };
-        Ok(content)
+    fn keys(&self) -> Box<dyn Iterator<Item = &Spec> + '_> {
+        Box::new(self.file_paths.keys())
     }
 
-    fn keys(&self) -> Vec<&Spec> {
-        self.file_paths.keys().collect()
+    fn contains(&self, key: &Spec) -> bool {
+        self.file_paths.contains_key(key)
     }
 }
 
 #[derive(Default)]
 pub struct FSSource {
     file_paths: FxHashMap<Spec, PathBuf>,
+    /// The contents of the files read so far, along with their modification
+    /// time when they were read.
+    cache: RwLock<FxHashMap<Spec, (SystemTime, Arc<str>)>>,
 }
 
 impl FSSource {
+    /// Creates a source with the given input file or with all the input
+    /// files in the given directory and its subdirectories (e.g.
+    /// `inputs/2022/day01_real.txt`).
     pub fn new(path: impl AsRef<Path>) -> Result<Self> {
         let mut source = FSSource::default();
-        let path = path.as_ref();
-        if path.is_file() {
-            source.add_path(path.to_path_buf())?;
-        } else {
-            for entry in std::fs::read_dir(path)? {
-                source.add_path(entry?.path())?;
-            }
-        }
-
+        source.add_dir(path)?;
         Ok(source)
     }
 
@@ -197,31 +448,84 @@ impl FSSource {
         source
     }
 
+    /// Adds the given input file or all the input files in the given
+    /// directory and its subdirectories. Files in the directory whose names
+    /// aren't input file names (e.g. a README) are ignored.
+    pub fn add_dir(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if path.is_file() {
+            return self.add_path(path.to_path_buf());
+        }
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                self.add_dir(entry.path())?;
+                continue;
+            }
+            match self.add_path(entry.path()) {
+                Err(Error::InvalidFileName { .. } | Error::InvalidFileEncodingInStr(_)) => {}
+                result => result?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds an input file, whose spec is given by its path. Fails if another
+    /// file with the same spec was already added.
     pub fn add_path(&mut self, path: PathBuf) -> Result<()> {
         let path_str = std::str::from_utf8(path.as_os_str().as_bytes())?;
         let spec = parse_input_filename(path_str)?;
-        self.add_path_with_spec(spec, path);
-        Ok(())
+        match self.file_paths.get(&spec) {
+            Some(old) if old != &path => Err(Error::DuplicateInputs {
+                filename1: old.to_string_lossy().into_owned(),
+                filename2: path.to_string_lossy().into_owned(),
+                spec,
+            }),
+            _ => {
+                self.add_path_with_spec(spec, path);
+                Ok(())
+            }
+        }
     }
 
+    /// Adds an input file with the given spec, replacing any file previously
+    /// added for that spec.
     pub fn add_path_with_spec(&mut self, spec: Spec, path: PathBuf) {
+        self.cache.get_mut().unwrap().remove(&spec);
         self.file_paths.insert(spec, path);
     }
 }
 
 impl Source for FSSource {
-    fn get(&self, spec: &Spec) -> Result<Cow<'_, str>> {
+    fn get(&self, spec: &Spec) -> Result<Content<'_>> {
         let file_path = self
             .file_paths
             .get(spec)
             .ok_or(Error::NoInputAvailable { spec: spec.clone() })?;
 
-        let content = std::fs::read_to_string(file_path)?;
-        Ok(Cow::Owned(content))
+        // Files are only read again if they were modified since the last read
+        let modified = std::fs::metadata(file_path)?.modified()?;
+        if let Some((cached_modified, content)) = self.cache.read().unwrap().get(spec) {
+            if *cached_modified == modified {
+                return Ok(Content::Shared(Arc::clone(content)));
+            }
+        }
+
+        let data = std::fs::read(file_path)?;
+        let content: Arc<str> = decode_file(&file_path.to_string_lossy(), Cow::Owned(data))?.into();
+        self.cache
+            .write()
+            .unwrap()
+            .insert(spec.clone(), (modified, Arc::clone(&content)));
+        Ok(Content::Shared(content))
     }
 
-    fn keys(&self) -> Vec<&Spec> {
-        self.file_paths.keys().collect()
+    fn keys(&self) -> Box<dyn Iterator<Item = &Spec> + '_> {
+        Box::new(self.file_paths.keys())
+    }
+
+    fn contains(&self, key: &Spec) -> bool {
+        self.file_paths.contains_key(key)
     }
 }
 
@@ -262,16 +566,20 @@ impl<'a> Default for MemorySource<'a> {
 }
 
 impl<'a> Source for MemorySource<'a> {
-    fn get(&self, spec: &Spec) -> Result<Cow<'_, str>> {
+    fn get(&self, spec: &Spec) -> Result<Content<'_>> {
         let content = self
             .files
             .get(spec)
             .ok_or(Error::NoInputAvailable { spec: spec.clone() })?;
-        Ok(Cow::Borrowed(content))
+        Ok(Content::Borrowed(content))
+    }
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &Spec> + '_> {
+        Box::new(self.files.keys())
     }
 
-    fn keys(&self) -> Vec<&Spec> {
-        self.files.keys().collect()
+    fn contains(&self, key: &Spec) -> bool {
+        self.files.contains_key(key)
     }
 }
 
@@ -287,16 +595,215 @@ impl<I1: Source, I2: Source> ChainedSource<I1, I2> {
 }
 
 impl<I1: Source, I2: Source> Source for ChainedSource<I1, I2> {
-    fn get(&self, spec: &Spec) -> Result<Cow<'_, str>> {
+    fn get(&self, spec: &Spec) -> Result<Content<'_>> {
         match self.first.get(spec) {
             Err(Error::NoInputAvailable { .. }) => self.second.get(spec),
             other => other,
         }
     }
 
-    fn keys(&self) -> Vec<&Spec> {
-        let mut keys = self.first.keys();
-        keys.append(&mut self.second.keys());
-        keys
+    fn keys(&self) -> Box<dyn Iterator<Item = &Spec> + '_> {
+        // Inputs in the first source shadow the ones in the second
+        let second = self.second.keys().filter(|spec| !self.first.contains(spec));
+        Box::new(self.first.keys().chain(second))
+    }
+
+    fn contains(&self, key: &Spec) -> bool {
+        self.first.contains(key) || self.second.contains(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    /// Creates an empty directory for a test, removing any leftovers from
+    /// previous runs.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-input-{}-{name}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn spec(s: &str) -> Spec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn add_dir_ignores_other_files() {
+        let dir = test_dir("stray");
+        std::fs::create_dir_all(dir.join("2022")).unwrap();
+        std::fs::write(dir.join("2022/day01_real.txt"), "1\n").unwrap();
+        std::fs::write(dir.join("2022/.day01_real.txt.swp"), "").unwrap();
+        std::fs::write(dir.join("README.md"), "").unwrap();
+
+        let source = FSSource::new(&dir).unwrap();
+        assert_eq!(source.keys().collect::<Vec<_>>(), [&spec("2022.1:real")]);
+        assert_eq!(&*source.get(&spec("2022.1:real")).unwrap(), "1\n");
+
+        // Files given explicitly must be inputs
+        let mut source = FSSource::default();
+        assert!(matches!(
+            source.add_dir(dir.join("README.md")),
+            Err(Error::InvalidFileName { .. })
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn compressed_file_names() {
+        for filename in [
+            "inputs/2022/day01_real.txt",
+            "inputs/2022/day01_real.txt.gz",
+            "inputs/2022/day01_real.txt.zst",
+            "inputs/2022/day01_real.txt.enc",
+            "inputs/2022/day01_real.txt.gz.enc",
+        ] {
+            assert_eq!(parse_input_filename(filename).unwrap(), spec("2022.1:real"));
+        }
+        for filename in [
+            "inputs/2022/day01_real.gz.txt",
+            "inputs/2022/day01_real.txt.enc.gz",
+            "inputs/2022/day01_real.txt.bz2",
+        ] {
+            assert!(matches!(
+                parse_input_filename(filename),
+                Err(Error::InvalidFileName { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn compressed_files_round_trip() {
+        let content = "1\n2\n3\n".repeat(100);
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(content.as_bytes()).unwrap();
+        let gz = encoder.finish().unwrap();
+        let decoded = decode_file("2022/day01_real.txt.gz", Cow::Owned(gz)).unwrap();
+        assert_eq!(decoded, content);
+
+        let zst = zstd::encode_all(content.as_bytes(), 0).unwrap();
+        let decoded = decode_file("2022/day01_real.txt.zst", Cow::Owned(zst)).unwrap();
+        assert_eq!(decoded, content);
+
+        // Uncompressed files aren't copied
+        let decoded = decode_file("2022/day01_real.txt", Cow::Borrowed(content.as_bytes()));
+        assert!(matches!(decoded, Ok(Cow::Borrowed(decoded)) if decoded == content));
+
+        let corrupted = decode_file("2022/day01_real.txt.gz", Cow::Borrowed(b"not gzip"));
+        assert!(corrupted.is_err());
+    }
+
+    #[test]
+    fn fs_source_shares_cached_contents() {
+        let dir = test_dir("cache");
+        let path = dir.join("day01_real.txt");
+        std::fs::write(&path, "1\n").unwrap();
+        let spec = spec("2022.1:real");
+        let source = FSSource::with_file(spec.clone(), &path);
+
+        let (Content::Shared(first), Content::Shared(second)) =
+            (source.get(&spec).unwrap(), source.get(&spec).unwrap())
+        else {
+            panic!("FSSource should share its cached contents");
+        };
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Modified files are read again
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_len(0).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(&*source.get(&spec).unwrap(), "");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn chained_sources_shadow_the_second_source() {
+        let mut first = MemorySource::new();
+        first.add_str(spec("2022.1:real"), "first");
+        let mut second = MemorySource::new();
+        second.add_str(spec("2022.1:real"), "second");
+        second.add_str(spec("2022.1:test"), "test");
+        let chained = chain(first, second);
+
+        assert_eq!(&*chained.get(&spec("2022.1:real")).unwrap(), "first");
+        assert_eq!(&*chained.get(&spec("2022.1:test")).unwrap(), "test");
+        assert!(matches!(
+            chained.get(&spec("2022.2:real")),
+            Err(Error::NoInputAvailable { .. })
+        ));
+        let mut keys: Vec<_> = chained.keys().map(ToString::to_string).collect();
+        keys.sort();
+        assert_eq!(keys, ["2022.1:real", "2022.1:test"]);
+    }
+
+    #[test]
+    fn input_without_front_matter() {
+        let input = Input::parse("1\n2\n").unwrap();
+        assert_eq!(input.text, "1\n2\n");
+        assert_eq!(input.params, Params::default());
+        assert_eq!(input.expected, [None, None]);
+    }
+
+    #[test]
+    fn input_with_front_matter() {
+        let input = Input::parse(
+            "---\nrows = 10\nname = \"a\"\n\n[expected]\npart1 = 26\npart2 = \"ABC\"\n---\n1\n",
+        )
+        .unwrap();
+        assert_eq!(input.text, "1\n");
+        assert_eq!(input.params.get::<u32>("rows").unwrap(), Some(10));
+        assert_eq!(
+            input.params.get::<String>("name").unwrap().as_deref(),
+            Some("a")
+        );
+        assert_eq!(input.params.get::<u32>("cols").unwrap(), None);
+        assert!(matches!(
+            input.params.get::<u32>("name"),
+            Err(Error::InvalidParam { .. })
+        ));
+        assert_eq!(
+            input.expected,
+            [Some("26".to_owned()), Some("ABC".to_owned())]
+        );
+    }
+
+    #[test]
+    fn front_matter_with_empty_expected_table() {
+        let input = Input::parse("---\n[expected]\n---\n1\n").unwrap();
+        assert_eq!(input.text, "1\n");
+        assert_eq!(input.expected, [None, None]);
+
+        let input = Input::parse("---\n---\n1\n").unwrap();
+        assert_eq!(input.text, "1\n");
+        assert_eq!(input.params, Params::default());
+    }
+
+    #[test]
+    fn front_matter_with_crlf_line_endings() {
+        let input =
+            Input::parse("---\r\nrows = 10\r\n[expected]\r\npart1 = 1\r\n---\r\n1\r\n").unwrap();
+        assert_eq!(input.text, "1\r\n");
+        assert_eq!(input.params.get::<u32>("rows").unwrap(), Some(10));
+        assert_eq!(input.expected, [Some("1".to_owned()), None]);
+    }
+
+    #[test]
+    fn invalid_front_matter() {
+        for content in [
+            "---\nrows = 10\n1\n",
+            "---\nrows = \n---\n1\n",
+            "---\nexpected = 1\n---\n1\n",
+        ] {
+            assert!(
+                matches!(Input::parse(content), Err(Error::InvalidFrontMatter { .. })),
+                "{content:?}"
+            );
+        }
     }
 }
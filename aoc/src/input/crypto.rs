@@ -0,0 +1,152 @@
+//! Encryption of input files, so that they can be committed to a public
+//! repository.
+//!
+//! Encrypted files have an extra `.enc` extension (e.g. `day01_real.txt.enc`)
+//! and contain a random nonce followed by the ChaCha20-Poly1305 ciphertext.
+
+use std::path::PathBuf;
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Nonce,
+};
+
+use super::{Error, Result};
+
+/// The environment variable containing the key (hex encoded).
+pub const KEY_ENV_VAR: &str = "AOC_INPUT_KEY";
+
+/// The environment variable containing the path of the key file, which
+/// defaults to [`DEFAULT_KEY_FILE`].
+pub const KEY_FILE_ENV_VAR: &str = "AOC_INPUT_KEY_FILE";
+
+/// The file containing the key (hex encoded) if [`KEY_ENV_VAR`] isn't set.
+pub const DEFAULT_KEY_FILE: &str = ".aoc-input-key";
+
+const NONCE_LEN: usize = 12;
+
+pub struct Key(chacha20poly1305::Key);
+
+impl Key {
+    #[must_use]
+    pub fn generate() -> Self {
+        Self(ChaCha20Poly1305::generate_key(&mut OsRng))
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let bytes = hex::decode(hex.trim()).map_err(|_| Error::InvalidKey)?;
+        if bytes.len() != 32 {
+            return Err(Error::InvalidKey);
+        }
+        Ok(Self(*chacha20poly1305::Key::from_slice(&bytes)))
+    }
+
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// The file the key is read from if [`KEY_ENV_VAR`] isn't set.
+    #[must_use]
+    pub fn file_path() -> PathBuf {
+        std::env::var_os(KEY_FILE_ENV_VAR).map_or_else(|| DEFAULT_KEY_FILE.into(), PathBuf::from)
+    }
+
+    /// Loads the key from [`KEY_ENV_VAR`] or from the key file, returning
+    /// `None` if neither is available.
+    pub fn load() -> Result<Option<Self>> {
+        if let Ok(hex) = std::env::var(KEY_ENV_VAR) {
+            return Self::from_hex(&hex).map(Some);
+        }
+        match std::fs::read_to_string(Self::file_path()) {
+            Ok(hex) => Self::from_hex(&hex).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    #[must_use]
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(&self.0)
+            .encrypt(&nonce, plaintext)
+            .expect("encryption failed");
+        [nonce.as_slice(), &ciphertext].concat()
+    }
+
+    /// Decrypts the contents of an encrypted file, failing if it wasn't
+    /// encrypted with this key.
+    pub fn decrypt(&self, filename: &str, data: &[u8]) -> Result<Vec<u8>> {
+        let failed = || Error::DecryptionFailed {
+            filename: filename.to_owned(),
+        };
+        if data.len() < NONCE_LEN {
+            return Err(failed());
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        ChaCha20Poly1305::new(&self.0)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| failed())
+    }
+}
+
+/// Decrypts an encrypted input file with the key given by [`Key::load`].
+pub(super) fn decrypt_file(filename: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let key = Key::load()?.ok_or_else(|| Error::MissingKey {
+        filename: filename.to_owned(),
+    })?;
+    key.decrypt(filename, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &[u8] = b"1000\n2000\n\n3000\n";
+
+    #[test]
+    fn encrypt_then_decrypt() {
+        let key = Key::generate();
+        let data = key.encrypt(PLAINTEXT);
+        assert_ne!(&data[NONCE_LEN..], PLAINTEXT);
+        assert_eq!(key.decrypt("day01_real.txt.enc", &data).unwrap(), PLAINTEXT);
+
+        // The key survives a round trip through its hex encoding
+        let key = Key::from_hex(&key.to_hex()).unwrap();
+        assert_eq!(key.decrypt("day01_real.txt.enc", &data).unwrap(), PLAINTEXT);
+    }
+
+    #[test]
+    fn decrypt_with_wrong_key() {
+        let data = Key::generate().encrypt(PLAINTEXT);
+        assert!(matches!(
+            Key::generate().decrypt("day01_real.txt.enc", &data),
+            Err(Error::DecryptionFailed { filename }) if filename == "day01_real.txt.enc"
+        ));
+    }
+
+    #[test]
+    fn decrypt_corrupted_data() {
+        let key = Key::generate();
+        let data = key.encrypt(PLAINTEXT);
+
+        let mut corrupted = data.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            key.decrypt("day01_real.txt.enc", &corrupted),
+            Err(Error::DecryptionFailed { .. })
+        ));
+
+        let truncated = &data[..NONCE_LEN - 1];
+        assert!(matches!(
+            key.decrypt("day01_real.txt.enc", truncated),
+            Err(Error::DecryptionFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_hex_keys() {
+        assert!(matches!(Key::from_hex("not hex"), Err(Error::InvalidKey)));
+        assert!(matches!(Key::from_hex("abcd"), Err(Error::InvalidKey)));
+    }
+}
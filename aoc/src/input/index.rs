@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use super::{Source, Spec};
+use crate::ProblemId;
+
+/// A sorted, deduplicated index of the inputs available in a [`Source`],
+/// supporting lookups by year, by problem and by variant pattern.
+#[derive(Debug, Clone, Default)]
+pub struct Index<'a> {
+    specs: BTreeMap<ProblemId, Vec<&'a Spec>>,
+}
+
+impl<'a> Index<'a> {
+    pub fn new(source: &'a (impl Source + ?Sized)) -> Self {
+        let mut specs: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for spec in source.keys() {
+            specs.entry(spec.id).or_default().push(spec);
+        }
+        for variants in specs.values_mut() {
+            variants.sort_unstable();
+            variants.dedup();
+        }
+        Self { specs }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.specs.values().map(Vec::len).sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.specs.is_empty()
+    }
+
+    /// Returns all the specs, sorted by problem and variant.
+    pub fn iter(&self) -> impl Iterator<Item = &'a Spec> + '_ {
+        self.specs.values().flatten().copied()
+    }
+
+    /// Returns the problems that have at least one input, in order.
+    pub fn ids(&self) -> impl Iterator<Item = ProblemId> + '_ {
+        self.specs.keys().copied()
+    }
+
+    pub fn by_year(&self, year: u32) -> impl Iterator<Item = &'a Spec> + '_ {
+        let start = ProblemId { year, day: 0 };
+        let end = ProblemId {
+            year,
+            day: u32::MAX,
+        };
+        self.specs.range(start..=end).flat_map(|(_, v)| v).copied()
+    }
+
+    pub fn by_id(&self, id: ProblemId) -> impl Iterator<Item = &'a Spec> + '_ {
+        self.specs.get(&id).into_iter().flatten().copied()
+    }
+
+    /// Returns the specs of the given year and day (or of any year or day, if
+    /// `None`) whose variant matches `variant` (see [`variant_matches`]).
+    #[must_use]
+    pub fn matching<'s>(
+        &'s self,
+        year: Option<u32>,
+        day: Option<u32>,
+        variant: Option<&'s str>,
+    ) -> Box<dyn Iterator<Item = &'a Spec> + 's> {
+        let specs: Box<dyn Iterator<Item = &'a Spec> + 's> = match (year, day) {
+            (Some(year), Some(day)) => Box::new(self.by_id(ProblemId { year, day })),
+            (Some(year), None) => Box::new(self.by_year(year)),
+            (None, Some(day)) => Box::new(self.iter().filter(move |spec| spec.id.day == day)),
+            (None, None) => Box::new(self.iter()),
+        };
+        match variant {
+            Some(pattern) => {
+                Box::new(specs.filter(move |spec| variant_matches(pattern, &spec.variant)))
+            }
+            None => specs,
+        }
+    }
+}
+
+/// Checks whether a variant matches a pattern, where `*` matches any
+/// sequence of characters (e.g. `test*` matches `test` and `test_part1`).
+#[must_use]
+pub fn variant_matches(pattern: &str, variant: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = variant.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        // No wildcards
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::MemorySource;
+
+    #[test]
+    fn variant_globs() {
+        assert!(variant_matches("test", "test"));
+        assert!(!variant_matches("test", "test1"));
+        assert!(variant_matches("test*", "test"));
+        assert!(variant_matches("test*", "test_part1"));
+        assert!(!variant_matches("test*", "real"));
+        assert!(variant_matches("*", "real"));
+        assert!(variant_matches("*1", "test1"));
+        assert!(!variant_matches("*1", "test2"));
+        assert!(variant_matches("t*_*2", "test_part2"));
+        assert!(!variant_matches("t*_*2", "test2"));
+        // The prefix and the suffix can't overlap
+        assert!(!variant_matches("ab*ba", "aba"));
+    }
+
+    fn source(specs: &[&str]) -> MemorySource<'static> {
+        let mut source = MemorySource::new();
+        for spec in specs {
+            source.add_str(spec.parse().unwrap(), "");
+        }
+        source
+    }
+
+    fn strings<'a>(specs: impl Iterator<Item = &'a Spec>) -> Vec<String> {
+        specs.map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn index_queries() {
+        let source = source(&[
+            "2022.2:test",
+            "2021.1:real",
+            "2022.1:test2",
+            "2022.1:real",
+            "2022.1:test1",
+        ]);
+        let index = Index::new(&source);
+        assert_eq!(index.len(), 5);
+        assert_eq!(
+            strings(index.iter()),
+            [
+                "2021.1:real",
+                "2022.1:real",
+                "2022.1:test1",
+                "2022.1:test2",
+                "2022.2:test"
+            ]
+        );
+        assert_eq!(
+            strings(index.by_year(2022)),
+            ["2022.1:real", "2022.1:test1", "2022.1:test2", "2022.2:test"]
+        );
+        assert_eq!(
+            strings(index.matching(None, Some(1), Some("real"))),
+            ["2021.1:real", "2022.1:real"]
+        );
+        assert_eq!(
+            strings(index.matching(Some(2022), None, Some("test*"))),
+            ["2022.1:test1", "2022.1:test2", "2022.2:test"]
+        );
+        assert_eq!(index.matching(Some(2020), None, None).count(), 0);
+    }
+
+    #[test]
+    fn index_of_chained_sources_has_no_duplicates() {
+        let first = source(&["2022.1:real", "2022.1:custom"]);
+        let second = source(&["2022.1:real", "2022.1:test"]);
+        let chained = crate::input::chain(first, second);
+        assert_eq!(
+            strings(Index::new(&chained).iter()),
+            ["2022.1:custom", "2022.1:real", "2022.1:test"]
+        );
+    }
+}
@@ -12,14 +12,17 @@
 
 use std::{
     fmt::{Display, Write},
-    time::Duration,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
 };
 
-use input::Spec;
+use input::{Params, Spec};
 use linkme::distributed_slice;
 use rustc_hash::FxHashMap;
+use serde::de::DeserializeOwned;
 use stats::{Monitor, Stats};
 
+pub mod alloc;
 pub mod input;
 pub mod stats;
 
@@ -39,6 +42,30 @@ pub enum SolverError {
     /// Wraps any other error raised by the solver.
     #[error("Solver error: {0}")]
     SolverError(#[source] anyhow::Error),
+
+    /// Indicates that the solver didn't finish in the allotted time.
+    #[error("Timed out after {0:?}")]
+    Timeout(Duration),
+
+    /// Indicates that the solver panicked (with the given message).
+    #[error("Solver panicked: {0}")]
+    Panic(String),
+}
+
+impl SolverError {
+    /// Returns an equivalent error. `SolverError` isn't `Clone`, so the copy
+    /// only keeps the kind and message of IO errors and the message (with
+    /// its causes) of solver errors.
+    #[must_use]
+    pub fn duplicate(&self) -> Self {
+        match self {
+            Self::IoError(e) => Self::IoError(std::io::Error::new(e.kind(), e.to_string())),
+            Self::NotImplemented => Self::NotImplemented,
+            Self::SolverError(e) => Self::SolverError(anyhow::anyhow!("{e:#}")),
+            Self::Timeout(timeout) => Self::Timeout(*timeout),
+            Self::Panic(msg) => Self::Panic(msg.clone()),
+        }
+    }
 }
 
 /// Indicates that a solver is not implemented by returning a
@@ -99,24 +126,85 @@ pub struct ProblemOutput<'a> {
     monitor: Monitor,
     state: OutputState,
     variant: String,
+    params: Params,
+    expected: [Option<String>; 2],
+    /// The only part whose solution is reported, if any.
+    selected_part: Option<Part>,
+    /// The first error returned by the writer while setting a solution,
+    /// which is reported by [`ProblemOutput::finish`].
+    write_failure: Option<SolverError>,
 }
 
 impl<'a> ProblemOutput<'a> {
-    pub fn start(spec: &Spec, writer: &'a mut (impl SolutionWriter + 'a)) -> Result<Self> {
+    pub fn start(spec: &Spec, writer: &'a mut (dyn SolutionWriter + 'a)) -> Result<Self> {
         writer.write_heading(spec)?;
         Ok(Self {
             writer,
             monitor: Monitor::new_at_current_instant(),
             state: OutputState::Visible,
             variant: spec.variant.clone(),
+            params: Params::default(),
+            expected: [None, None],
+            selected_part: None,
+            write_failure: None,
         })
     }
 
+    /// Sets the parameters of the input (see [`input::Input`]).
+    #[must_use]
+    pub fn with_params(mut self, params: Params) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Sets the expected answers of the input. Solutions that don't match
+    /// them are reported with [`SolutionWriter::write_mismatch`].
+    #[must_use]
+    pub fn with_expected(mut self, expected: [Option<String>; 2]) -> Self {
+        self.expected = expected;
+        self
+    }
+
+    /// Only reports the solution of `part` (or of both parts if `None`).
+    ///
+    /// The solution of the other part is discarded without being measured,
+    /// whether it's set eagerly (e.g. [`ProblemOutput::set_part1`]) or lazily
+    /// (e.g. [`ProblemOutput::part1_with`]): the time since the previous part
+    /// keeps running and is attributed to the next reported part. That way
+    /// the work actually done is always counted, including work shared by
+    /// both parts, and solvers that check [`ProblemOutput::wants`] don't pay
+    /// for the other part.
+    #[must_use]
+    pub fn with_part(mut self, part: Option<Part>) -> Self {
+        self.selected_part = part;
+        self
+    }
+
     #[must_use]
     pub fn variant(&self) -> &str {
         &self.variant
     }
 
+    /// Returns the input parameter `name`, failing if the input doesn't
+    /// define it.
+    pub fn param<T: DeserializeOwned>(&self, name: &str) -> std::result::Result<T, input::Error> {
+        self.params
+            .get(name)?
+            .ok_or_else(|| input::Error::MissingParam {
+                name: name.to_owned(),
+            })
+    }
+
+    /// Returns the input parameter `name`, or `default` if the input doesn't
+    /// define it.
+    pub fn param_or<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        default: T,
+    ) -> std::result::Result<T, input::Error> {
+        Ok(self.params.get(name)?.unwrap_or(default))
+    }
+
     pub fn writer(&mut self) -> &mut dyn SolutionWriter {
         self.writer
     }
@@ -125,6 +213,27 @@ impl<'a> ProblemOutput<'a> {
         self.monitor.reset();
     }
 
+    /// Excludes the first `runs` executions of each part from the
+    /// statistics (see [`Monitor::set_warmup_runs`]).
+    pub fn set_warmup_runs(&mut self, runs: usize) {
+        self.monitor.set_warmup_runs(runs);
+    }
+
+    /// Ends a phase of the solver (e.g. parsing the input), attributing the
+    /// time elapsed since the previous phase or part to `name`.
+    pub fn phase(&mut self, name: &'static str) {
+        self.monitor.finish_phase(name);
+    }
+
+    /// Runs `f`, timing it as the phase `name`. Its execution time is excluded
+    /// from the surrounding phase or part.
+    pub fn in_phase<T>(&mut self, name: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.monitor.add_nested_phase(name, start.elapsed());
+        result
+    }
+
     #[must_use]
     pub fn total_time(&self) -> Duration {
         self.monitor.total_time()
@@ -147,32 +256,109 @@ impl<'a> ProblemOutput<'a> {
                 if let Some(solution) = &solutions[part.to_index()] {
                     let stats = &self.monitor.stats(part);
                     self.writer.write_solution(part, stats, solution)?;
+                    Self::check_expected(self.writer, &self.expected, part, solution)?;
                 }
             }
         }
         Ok(())
     }
 
+    /// Writes the time breakdown per phase (if the solver defined any phases)
+    /// and the footer of the problem. This should be called once the solver
+    /// has finished running.
+    ///
+    /// Fails if the writer failed while writing a solution.
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some(e) = self.write_failure.take() {
+            return Err(e);
+        }
+        if self.monitor.has_phases() {
+            let phases = self.monitor.phase_stats();
+            self.writer
+                .write_phases(&phases, self.monitor.part_stats())?;
+        }
+        self.writer.write_footer(self.monitor.dropped_time())
+    }
+
+    /// Returns whether the solution of `part` will be reported. Solvers can
+    /// use it to skip work that is only needed by the other part.
+    #[must_use]
+    pub fn wants(&self, part: Part) -> bool {
+        self.selected_part.is_none_or(|selected| selected == part)
+    }
+
     fn try_set(&mut self, part: Part, solution: impl Display) -> Result<()> {
+        if !self.wants(part) {
+            // Not measured (see `with_part`)
+            return Ok(());
+        }
         self.monitor.finish(part);
+        let result = self.write_solution(part, solution);
+        // The solver keeps running even if the writer failed, so the next part
+        // must be measured either way
+        self.monitor.reset();
+        result
+    }
+
+    fn write_solution(&mut self, part: Part, solution: impl Display) -> Result<()> {
         if let OutputState::Hidden(solutions) = &mut self.state {
             solutions[part.to_index()] = Some(solution.to_string());
         } else {
             self.writer
                 .write_solution(part, &self.monitor.stats(part), &solution)?;
+            if self.expected[part.to_index()].is_some() {
+                let solution = solution.to_string();
+                Self::check_expected(self.writer, &self.expected, part, &solution)?;
+            }
         }
-        self.monitor.reset();
         Ok(())
     }
 
+    fn check_expected(
+        writer: &mut dyn SolutionWriter,
+        expected: &[Option<String>; 2],
+        part: Part,
+        solution: &str,
+    ) -> Result<()> {
+        match &expected[part.to_index()] {
+            Some(expected) if expected != solution => {
+                writer.write_mismatch(part, expected, solution)
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn set_part1(&mut self, solution: impl Display) {
-        self.try_set(Part::One, solution)
-            .expect("Unexpected error setting the output for part 1");
+        self.set(Part::One, solution);
     }
 
     pub fn set_part2(&mut self, solution: impl Display) {
-        self.try_set(Part::Two, solution)
-            .expect("Unexpected error setting the output for part 2");
+        self.set(Part::Two, solution);
+    }
+
+    /// Computes and sets the solution of part 1, only calling `f` if the
+    /// part is wanted (see [`ProblemOutput::with_part`]).
+    pub fn part1_with<T: Display>(&mut self, f: impl FnOnce() -> T) {
+        self.set_with(Part::One, f);
+    }
+
+    /// Computes and sets the solution of part 2, only calling `f` if the
+    /// part is wanted (see [`ProblemOutput::with_part`]).
+    pub fn part2_with<T: Display>(&mut self, f: impl FnOnce() -> T) {
+        self.set_with(Part::Two, f);
+    }
+
+    fn set_with<T: Display>(&mut self, part: Part, f: impl FnOnce() -> T) {
+        if self.wants(part) {
+            self.set(part, f());
+        }
+    }
+
+    fn set(&mut self, part: Part, solution: impl Display) {
+        if let Err(e) = self.try_set(part, solution) {
+            // Solvers can't handle writer errors, so they're reported later
+            self.write_failure.get_or_insert(e);
+        }
     }
 }
 
@@ -185,6 +371,78 @@ pub trait SolutionWriter {
     fn write_heading(&mut self, spec: &Spec) -> Result<()>;
     fn write_solution(&mut self, part: Part, monitor: &Stats, solution: &dyn Display)
     -> Result<()>;
+
+    /// Reports an error returned by the solver of the current problem.
+    fn write_error(&mut self, _err: &SolverError) -> Result<()> {
+        Ok(())
+    }
+
+    /// Reports a solution that doesn't match the expected answer given by the
+    /// input (see [`input::Input`]). Called after `write_solution`.
+    fn write_mismatch(&mut self, _part: Part, _expected: &str, _actual: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Reports a warning about the current problem (e.g. too much time was
+    /// dropped while benchmarking).
+    fn write_warning(&mut self, _msg: &dyn Display) -> Result<()> {
+        Ok(())
+    }
+
+    /// Reports the time spent in each phase of the solver, along with the
+    /// time spent in each part (excluding the phases), so that the total
+    /// time can be broken down.
+    fn write_phases(
+        &mut self,
+        _phases: &[(&str, Stats)],
+        _parts: [Option<Stats>; 2],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called after the solver of the current problem has finished running.
+    fn write_footer(&mut self, _dropped_time: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called once after all problems have been written.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<W: SolutionWriter + ?Sized> SolutionWriter for &mut W {
+    fn write_heading(&mut self, spec: &Spec) -> Result<()> {
+        (**self).write_heading(spec)
+    }
+
+    fn write_solution(&mut self, part: Part, stats: &Stats, solution: &dyn Display) -> Result<()> {
+        (**self).write_solution(part, stats, solution)
+    }
+
+    fn write_error(&mut self, err: &SolverError) -> Result<()> {
+        (**self).write_error(err)
+    }
+
+    fn write_mismatch(&mut self, part: Part, expected: &str, actual: &str) -> Result<()> {
+        (**self).write_mismatch(part, expected, actual)
+    }
+
+    fn write_warning(&mut self, msg: &dyn Display) -> Result<()> {
+        (**self).write_warning(msg)
+    }
+
+    fn write_phases(&mut self, phases: &[(&str, Stats)], parts: [Option<Stats>; 2]) -> Result<()> {
+        (**self).write_phases(phases, parts)
+    }
+
+    fn write_footer(&mut self, dropped_time: Duration) -> Result<()> {
+        (**self).write_footer(dropped_time)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        (**self).finish()
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -205,6 +463,110 @@ impl SolutionWriter for NullWriter {
     }
 }
 
+/// A solution that didn't match the expected answer given by the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub part: Part,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Everything a solver wrote for a single problem, as collected by a
+/// [`CollectingWriter`].
+#[derive(Debug, Default)]
+pub struct SolveReport {
+    pub answers: [Option<String>; 2],
+    pub stats: [Option<Stats>; 2],
+    pub phases: Vec<(String, Stats)>,
+    pub mismatches: Vec<Mismatch>,
+    pub warnings: Vec<String>,
+    pub error: Option<SolverError>,
+    pub dropped_time: Duration,
+}
+
+impl SolveReport {
+    #[must_use]
+    pub fn answer(&self, part: Part) -> Option<&str> {
+        self.answers[part.to_index()].as_deref()
+    }
+
+    /// Checks whether the solver ran without errors and its answers matched
+    /// the expected ones (if any).
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none() && self.mismatches.is_empty()
+    }
+}
+
+/// A [`SolutionWriter`] that keeps everything written for a problem in a
+/// [`SolveReport`] instead of printing it.
+#[derive(Debug, Default)]
+pub struct CollectingWriter {
+    report: SolveReport,
+}
+
+impl CollectingWriter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn report(&self) -> &SolveReport {
+        &self.report
+    }
+
+    #[must_use]
+    pub fn into_report(self) -> SolveReport {
+        self.report
+    }
+}
+
+impl SolutionWriter for CollectingWriter {
+    fn write_heading(&mut self, _spec: &Spec) -> Result<()> {
+        self.report = SolveReport::default();
+        Ok(())
+    }
+
+    fn write_solution(&mut self, part: Part, stats: &Stats, solution: &dyn Display) -> Result<()> {
+        self.report.answers[part.to_index()] = Some(solution.to_string());
+        self.report.stats[part.to_index()] = Some(*stats);
+        Ok(())
+    }
+
+    fn write_error(&mut self, err: &SolverError) -> Result<()> {
+        self.report.error = Some(err.duplicate());
+        Ok(())
+    }
+
+    fn write_mismatch(&mut self, part: Part, expected: &str, actual: &str) -> Result<()> {
+        self.report.mismatches.push(Mismatch {
+            part,
+            expected: expected.to_owned(),
+            actual: actual.to_owned(),
+        });
+        Ok(())
+    }
+
+    fn write_warning(&mut self, msg: &dyn Display) -> Result<()> {
+        self.report.warnings.push(msg.to_string());
+        Ok(())
+    }
+
+    fn write_phases(&mut self, phases: &[(&str, Stats)], _parts: [Option<Stats>; 2]) -> Result<()> {
+        self.report.phases = phases
+            .iter()
+            .map(|(name, stats)| ((*name).to_owned(), *stats))
+            .collect();
+        Ok(())
+    }
+
+    fn write_footer(&mut self, dropped_time: Duration) -> Result<()> {
+        self.report.dropped_time = dropped_time;
+        Ok(())
+    }
+}
+
 pub struct Solver {
     pub problem_id: ProblemId,
     pub raw_solve: fn(input: &str, out: &mut ProblemOutput<'_>) -> anyhow::Result<()>,
@@ -221,13 +583,70 @@ impl std::fmt::Debug for Solver {
 }
 
 impl Solver {
+    /// Runs the solver on `input`. Panics are caught and returned as
+    /// [`SolverError::Panic`].
     pub fn solve(&self, input: &str, out: &mut ProblemOutput<'_>) -> Result<()> {
-        (self.raw_solve)(input, out).map_err(|e| match e.downcast() {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| (self.raw_solve)(input, out)))
+            .map_err(|payload| {
+                let msg = payload
+                    .downcast_ref::<&str>()
+                    .map(|msg| (*msg).to_owned())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "<unknown panic payload>".to_owned());
+                SolverError::Panic(msg)
+            })?;
+        result.map_err(|e| match e.downcast() {
             Ok(e) => e,
             Err(e) => SolverError::SolverError(e),
         })
     }
 
+    /// Runs the solver once on `input`, returning its answers, their
+    /// statistics and any error as data instead of writing them.
+    ///
+    /// The input is run as the `custom` variant.
+    #[must_use]
+    pub fn run(&self, input: &input::Input<'_>) -> SolveReport {
+        let spec = Spec {
+            id: self.problem_id,
+            variant: "custom".to_owned(),
+        };
+        let mut writer = CollectingWriter::new();
+        let mut out = ProblemOutput::start(&spec, &mut writer)
+            .expect("Unexpected error collecting the output")
+            .with_params(input.params.clone())
+            .with_expected(input.expected.clone());
+        let result = self.solve(input.text, &mut out);
+        out.finish()
+            .expect("Unexpected error collecting the output");
+        // Keep the original error instead of the copy kept by the writer
+        let mut report = writer.into_report();
+        report.error = result.err();
+        report
+    }
+
+    /// Runs the solver once on `input` (the input of `spec`), reporting
+    /// the answer to `part` (or to both parts if `None`) and any error
+    /// returned by the solver to `writer`.
+    ///
+    /// Only errors returned by the writer are returned.
+    pub fn run_with(
+        &self,
+        spec: &Spec,
+        input: &input::Input<'_>,
+        part: Option<Part>,
+        writer: &mut dyn SolutionWriter,
+    ) -> Result<()> {
+        let mut out = ProblemOutput::start(spec, writer)?
+            .with_params(input.params.clone())
+            .with_expected(input.expected.clone())
+            .with_part(part);
+        if let Err(e) = self.solve(input.text, &mut out) {
+            out.writer().write_error(&e)?;
+        }
+        out.finish()
+    }
+
     #[must_use]
     pub fn get_map() -> FxHashMap<ProblemId, &'static Solver> {
         let mut m: FxHashMap<ProblemId, &'static Solver> = FxHashMap::default();
@@ -260,3 +679,82 @@ macro_rules! register {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A writer that fails to write solutions.
+    struct FailingWriter {
+        errors: Vec<String>,
+    }
+
+    impl SolutionWriter for FailingWriter {
+        fn write_heading(&mut self, _spec: &Spec) -> Result<()> {
+            Ok(())
+        }
+
+        fn write_solution(
+            &mut self,
+            _part: Part,
+            _stats: &Stats,
+            _solution: &dyn Display,
+        ) -> Result<()> {
+            Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "broken pipe").into())
+        }
+
+        fn write_error(&mut self, err: &SolverError) -> Result<()> {
+            self.errors.push(err.to_string());
+            Ok(())
+        }
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn solve_both_parts(_input: &str, out: &mut ProblemOutput<'_>) -> anyhow::Result<()> {
+        out.set_part1(1);
+        out.set_part2(2);
+        Ok(())
+    }
+
+    #[test]
+    fn writer_errors_are_reported_after_solving() {
+        let solver = Solver {
+            problem_id: ProblemId { year: 2022, day: 1 },
+            raw_solve: solve_both_parts,
+        };
+        let spec = Spec {
+            id: solver.problem_id,
+            variant: "test".to_owned(),
+        };
+        let mut writer = FailingWriter { errors: Vec::new() };
+        let result = solver.run_with(&spec, &input::Input::default(), None, &mut writer);
+
+        // The solver finished normally, so the write error is the only error
+        assert!(matches!(result, Err(SolverError::IoError(_))), "{result:?}");
+        assert!(writer.errors.is_empty(), "{:?}", writer.errors);
+    }
+
+    #[test]
+    fn only_the_selected_part_is_reported() {
+        let spec = Spec {
+            id: ProblemId { year: 2022, day: 1 },
+            variant: "test".to_owned(),
+        };
+        let mut writer = CollectingWriter::new();
+        {
+            let mut out = ProblemOutput::start(&spec, &mut writer)
+                .unwrap()
+                .with_part(Some(Part::Two));
+            assert!(!out.wants(Part::One));
+            assert!(out.wants(Part::Two));
+            out.set_part1(1);
+            out.part1_with(|| -> u32 { panic!("part 1 isn't wanted") });
+            out.part2_with(|| 2);
+            out.finish().unwrap();
+        }
+        let report = writer.into_report();
+        assert_eq!(report.answers, [None, Some("2".to_owned())]);
+        assert!(report.stats[0].is_none());
+        assert_eq!(report.stats[1].unwrap().exec_count, 1);
+    }
+}
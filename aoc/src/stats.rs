@@ -1,13 +1,35 @@
 use std::time::{Duration, Instant};
 
-use crate::Part;
+use crate::{
+    alloc::{self, Checkpoint, MemoryStats},
+    Part,
+};
+
+/// Modified z-score above which an execution time is considered an outlier
+/// (as recommended by Iglewicz and Hoaglin).
+const OUTLIER_Z_SCORE: f64 = 3.5;
 
 #[derive(Default)]
 pub struct Monitor {
     exec_times: [Vec<Duration>; 2],
+    /// The memory used by each execution of each part (only recorded if
+    /// [`alloc::is_enabled`]).
+    memory: [Vec<MemoryStats>; 2],
+    alloc_checkpoint: Checkpoint,
+    phases: Vec<Phase>,
     current: Option<Instant>,
     total_time: Duration,
     dropped_time: Duration,
+    warmup_runs: [usize; 2],
+    warmup_runs_per_phase: usize,
+}
+
+/// A named section of a solver (e.g. parsing), timed separately from the
+/// parts.
+struct Phase {
+    name: &'static str,
+    exec_times: Vec<Duration>,
+    warmup_runs: usize,
 }
 
 impl Monitor {
@@ -15,6 +37,7 @@ impl Monitor {
     pub fn new_at_current_instant() -> Self {
         Self {
             current: Some(Instant::now()),
+            alloc_checkpoint: Checkpoint::now(),
             ..Default::default()
         }
     }
@@ -24,21 +47,107 @@ impl Monitor {
         if let Some(c) = self.current.replace(now) {
             self.dropped_time += now.duration_since(c);
         }
+        self.alloc_checkpoint = Checkpoint::now();
+    }
+
+    /// Discards the first `runs` executions of each part and phase, so that
+    /// they are not included in the statistics, the total time or the dropped
+    /// time.
+    pub fn set_warmup_runs(&mut self, runs: usize) {
+        self.warmup_runs = [runs; 2];
+        self.warmup_runs_per_phase = runs;
+        for phase in &mut self.phases {
+            phase.warmup_runs = runs;
+        }
     }
 
     pub fn finish(&mut self, part: Part) {
         if let Some(t) = self.current.take() {
             let elapsed = t.elapsed();
-            self.total_time += elapsed;
-            self.exec_times[part.to_index()].push(elapsed);
+            let warmup_runs = &mut self.warmup_runs[part.to_index()];
+            if *warmup_runs > 0 {
+                *warmup_runs -= 1;
+            } else {
+                self.total_time += elapsed;
+                self.exec_times[part.to_index()].push(elapsed);
+                if alloc::is_enabled() {
+                    self.memory[part.to_index()].push(self.alloc_checkpoint.memory_stats());
+                }
+            }
         } else {
             panic!("Nothing to finish");
         }
     }
 
+    /// Attributes the time elapsed since the last checkpoint to the phase
+    /// `name` and starts measuring the next phase or part.
+    pub fn finish_phase(&mut self, name: &'static str) {
+        let now = Instant::now();
+        if let Some(t) = self.current.replace(now) {
+            self.record_phase(name, now.duration_since(t));
+            self.alloc_checkpoint = Checkpoint::now();
+        } else {
+            panic!("Nothing to finish");
+        }
+    }
+
+    /// Records an execution of the phase `name` that was measured separately,
+    /// excluding it from the phase or part that is currently being measured.
+    pub fn add_nested_phase(&mut self, name: &'static str, elapsed: Duration) {
+        if let Some(current) = &mut self.current {
+            *current += elapsed;
+        }
+        self.record_phase(name, elapsed);
+    }
+
+    fn record_phase(&mut self, name: &'static str, elapsed: Duration) {
+        let phase = if let Some(i) = self.phases.iter().position(|p| p.name == name) {
+            &mut self.phases[i]
+        } else {
+            self.phases.push(Phase {
+                name,
+                exec_times: Vec::new(),
+                warmup_runs: self.warmup_runs_per_phase,
+            });
+            self.phases.last_mut().unwrap()
+        };
+        if phase.warmup_runs > 0 {
+            phase.warmup_runs -= 1;
+        } else {
+            self.total_time += elapsed;
+            phase.exec_times.push(elapsed);
+        }
+    }
+
     #[must_use]
     pub fn stats(&self, part: Part) -> Stats {
-        Stats::new(&self.exec_times[part.to_index()])
+        let mut stats = Stats::new(&self.exec_times[part.to_index()]);
+        stats.memory = combine_memory_stats(&self.memory[part.to_index()]);
+        stats
+    }
+
+    /// Returns the statistics of each part that has been executed at least
+    /// once.
+    #[must_use]
+    pub fn part_stats(&self) -> [Option<Stats>; 2] {
+        [Part::One, Part::Two]
+            .map(|part| (!self.exec_times[part.to_index()].is_empty()).then(|| self.stats(part)))
+    }
+
+    /// Returns the statistics of each phase, in the order in which they were
+    /// first executed.
+    #[must_use]
+    pub fn phase_stats(&self) -> Vec<(&'static str, Stats)> {
+        self.phases
+            .iter()
+            .filter(|phase| !phase.exec_times.is_empty())
+            .map(|phase| (phase.name, Stats::new(&phase.exec_times)))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn has_phases(&self) -> bool {
+        !self.phases.is_empty()
     }
 
     #[must_use]
@@ -58,6 +167,19 @@ pub struct Stats {
     pub exec_time_total: Duration,
     pub exec_time_mean: Duration,
     pub exec_time_std: Option<Duration>,
+    pub exec_time_min: Duration,
+    pub exec_time_max: Duration,
+    pub exec_time_median: Duration,
+    /// The 5th percentile of the execution times.
+    pub exec_time_p5: Duration,
+    /// The 95th percentile of the execution times.
+    pub exec_time_p95: Duration,
+    /// The number of executions whose time is too far from the median, as
+    /// measured by the median absolute deviation (MAD).
+    pub outliers: usize,
+    /// The memory used by the executions, if allocations are tracked (see
+    /// [`alloc`]).
+    pub memory: Option<MemoryStats>,
 }
 
 impl Stats {
@@ -77,11 +199,22 @@ impl Stats {
             let secs = (exec_time_err2_secs / (exec_times.len() - 1) as f64).sqrt();
             Some(Duration::from_secs_f64(secs))
         };
+
+        let mut sorted = exec_times.to_vec();
+        sorted.sort_unstable();
+        let exec_time_median = percentile(&sorted, 0.5);
         Self {
             exec_count: exec_times.len(),
             exec_time_total,
             exec_time_mean,
             exec_time_std,
+            exec_time_min: sorted[0],
+            exec_time_max: sorted[sorted.len() - 1],
+            exec_time_median,
+            exec_time_p5: percentile(&sorted, 0.05),
+            exec_time_p95: percentile(&sorted, 0.95),
+            outliers: count_outliers(&sorted, exec_time_median),
+            memory: None,
         }
     }
 
@@ -92,3 +225,265 @@ impl Stats {
         total_time
     }
 }
+
+/// Combines the memory used by several executions, keeping the run with the
+/// highest peak, so that the peak and the allocations describe the same run.
+fn combine_memory_stats(runs: &[MemoryStats]) -> Option<MemoryStats> {
+    runs.iter()
+        .max_by_key(|m| (m.peak_bytes, m.allocations))
+        .copied()
+}
+
+/// Computes the `q`-th quantile of `sorted` (which must not be empty),
+/// interpolating linearly between the closest ranks.
+fn percentile(sorted: &[Duration], q: f64) -> Duration {
+    #[allow(clippy::cast_precision_loss)]
+    let rank = q * (sorted.len() - 1) as f64;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    let (lower, upper) = (sorted[lower], sorted[upper]);
+    lower + upper.saturating_sub(lower).mul_f64(rank.fract())
+}
+
+/// Counts the values whose modified z-score (based on the median absolute
+/// deviation) exceeds [`OUTLIER_Z_SCORE`].
+fn count_outliers(sorted: &[Duration], median: Duration) -> usize {
+    let mut deviations: Vec<Duration> = sorted.iter().map(|&d| d.abs_diff(median)).collect();
+    deviations.sort_unstable();
+    let mad = percentile(&deviations, 0.5).as_secs_f64();
+    if mad == 0.0 {
+        return 0;
+    }
+    // 0.6745 is the 75th percentile of the standard normal distribution, which
+    // makes the MAD comparable to the standard deviation
+    deviations
+        .iter()
+        .filter(|dev| 0.6745 * dev.as_secs_f64() / mad > OUTLIER_Z_SCORE)
+        .count()
+}
+
+/// Performs Welch's t-test on the execution times of `a` and `b`, returning
+/// the two-sided p-value of the hypothesis that both have the same mean.
+///
+/// Returns `None` if either sample doesn't have a standard deviation (i.e.
+/// it has a single execution).
+#[must_use]
+pub fn welch_t_test(a: &Stats, b: &Stats) -> Option<f64> {
+    #[allow(clippy::cast_precision_loss)]
+    let variance_of_mean =
+        |s: &Stats| Some(s.exec_time_std?.as_secs_f64().powi(2) / s.exec_count as f64);
+    let va = variance_of_mean(a)?;
+    let vb = variance_of_mean(b)?;
+    let diff = a.exec_time_mean.as_secs_f64() - b.exec_time_mean.as_secs_f64();
+    if va + vb == 0.0 {
+        return Some(if diff == 0.0 { 1.0 } else { 0.0 });
+    }
+
+    let t = diff / (va + vb).sqrt();
+    // Welch–Satterthwaite approximation of the degrees of freedom
+    #[allow(clippy::cast_precision_loss)]
+    let df = (va + vb).powi(2)
+        / (va.powi(2) / (a.exec_count - 1) as f64 + vb.powi(2) / (b.exec_count - 1) as f64);
+    Some(regularized_incomplete_beta(
+        df / (df + t * t),
+        df / 2.0,
+        0.5,
+    ))
+}
+
+/// The regularized incomplete beta function `I_x(a, b)`, evaluated with the
+/// continued fraction from Numerical Recipes.
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    // The continued fraction converges quickly only for x < (a + 1) / (a + b + 2)
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+#[allow(clippy::many_single_char_names)]
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATIONS: u32 = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let clamp = |v: f64| if v.abs() < TINY { TINY } else { v };
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut result = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = f64::from(m);
+        let m2 = 2.0 * m;
+
+        let even = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 / clamp(1.0 + even * d);
+        c = clamp(1.0 + even / c);
+        result *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 / clamp(1.0 + odd * d);
+        c = clamp(1.0 + odd / c);
+        let delta = d * c;
+        result *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    result
+}
+
+/// The natural logarithm of the gamma function (Lanczos approximation).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000_000_000_190_015;
+    let mut y = x;
+    for c in COEFFICIENTS {
+        y += 1.0;
+        series += c / y;
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn phases_and_parts_add_up_to_the_total_time() {
+        let mut monitor = Monitor::new_at_current_instant();
+        monitor.set_warmup_runs(1);
+        for run in 0..3 {
+            monitor.finish_phase("parse");
+            monitor.finish(Part::One);
+            monitor.reset();
+            // The warm-up run is much slower, as it would be with cold caches
+            let search = Duration::from_millis(if run == 0 { 1000 } else { run });
+            monitor.add_nested_phase("search", search);
+            monitor.finish(Part::Two);
+            monitor.reset();
+        }
+
+        let phases = monitor.phase_stats();
+        let names: Vec<_> = phases.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["parse", "search"]);
+        assert_eq!(phases[1].1.exec_time_total, Duration::from_millis(3));
+        let [Some(part1), Some(part2)] = monitor.part_stats() else {
+            panic!("both parts should have been measured");
+        };
+        let all_stats: Vec<_> = phases
+            .iter()
+            .map(|(_, stats)| stats)
+            .chain([&part1, &part2])
+            .collect();
+        assert!(all_stats.iter().all(|stats| stats.exec_count == 2));
+        let total: Duration = all_stats.iter().map(|stats| stats.exec_time_total).sum();
+        assert_eq!(total, monitor.total_time());
+    }
+
+    #[test]
+    fn percentiles_interpolate_between_ranks() {
+        let stats = Stats::new(&millis(&[4, 1, 10, 3, 2]));
+        assert_eq!(stats.exec_time_min, Duration::from_millis(1));
+        assert_eq!(stats.exec_time_max, Duration::from_millis(10));
+        assert_eq!(stats.exec_time_median, Duration::from_millis(3));
+        assert_eq!(stats.exec_time_p5, Duration::from_micros(1200));
+        assert_eq!(stats.exec_time_p95, Duration::from_micros(8800));
+        assert_eq!(stats.exec_time_mean, Duration::from_millis(4));
+    }
+
+    #[test]
+    fn outliers_use_the_median_absolute_deviation() {
+        // The MAD is 1ms, so only 10ms (modified z-score 4.7) is an outlier
+        assert_eq!(Stats::new(&millis(&[1, 2, 3, 4, 10])).outliers, 1);
+        assert_eq!(Stats::new(&millis(&[1, 2, 3, 4, 5])).outliers, 0);
+        // A MAD of zero doesn't make every other value an outlier
+        assert_eq!(Stats::new(&millis(&[3, 3, 3, 3, 100])).outliers, 0);
+    }
+
+    #[test]
+    fn memory_stats_come_from_the_peak_run() {
+        let run = |allocations, peak_bytes| MemoryStats {
+            allocations,
+            peak_bytes,
+        };
+        // A run that frees its memory before allocating again can make more
+        // allocations with a lower peak
+        let runs = [run(10, 64), run(3, 1024), run(100, 512)];
+        assert_eq!(combine_memory_stats(&runs), Some(run(3, 1024)));
+        assert_eq!(combine_memory_stats(&[]), None);
+    }
+
+    #[test]
+    fn ln_gamma_known_values() {
+        assert_close(ln_gamma(1.0), 0.0, 1e-10);
+        assert_close(ln_gamma(5.0), 24f64.ln(), 1e-10);
+        assert_close(ln_gamma(0.5), std::f64::consts::PI.sqrt().ln(), 1e-10);
+    }
+
+    #[test]
+    fn regularized_incomplete_beta_known_values() {
+        assert_close(regularized_incomplete_beta(0.3, 1.0, 1.0), 0.3, 1e-10);
+        assert_close(regularized_incomplete_beta(0.3, 2.0, 1.0), 0.09, 1e-10);
+        assert_close(regularized_incomplete_beta(0.5, 4.5, 4.5), 0.5, 1e-10);
+        assert_close(regularized_incomplete_beta(0.0, 2.0, 3.0), 0.0, 1e-10);
+        assert_close(regularized_incomplete_beta(1.0, 2.0, 3.0), 1.0, 1e-10);
+    }
+
+    #[test]
+    fn welch_t_test_textbook_example() {
+        // Example 1 of the Wikipedia article on Welch's t-test (in units of
+        // 100µs): t = -2.22, df = 24.5 and p = 0.036
+        let a = [198, 204, 196, 178, 185, 189, 183, 189, 195, 220];
+        let b = [
+            282, 266, 201, 233, 252, 221, 177, 276, 206, 137, 232, 175, 206, 180, 239, 216, 243,
+            204, 239, 133,
+        ];
+        let stats = |values: &[u64]| {
+            let times: Vec<_> = values
+                .iter()
+                .map(|&v| Duration::from_micros(v * 100))
+                .collect();
+            Stats::new(&times)
+        };
+        let p = welch_t_test(&stats(&a), &stats(&b)).unwrap();
+        assert_close(p, 0.035_48, 1e-4);
+        assert_close(welch_t_test(&stats(&b), &stats(&a)).unwrap(), p, 1e-12);
+    }
+
+    #[test]
+    fn welch_t_test_needs_several_runs() {
+        let single = Stats::new(&millis(&[5]));
+        let several = Stats::new(&millis(&[4, 5, 6]));
+        assert_eq!(welch_t_test(&single, &several), None);
+        assert_eq!(welch_t_test(&several, &several), Some(1.0));
+    }
+}
@@ -0,0 +1,295 @@
+use std::{collections::BTreeMap, fmt::Display, path::Path, time::Duration};
+
+use anyhow::Context;
+use aoc::{input::Spec, stats::Stats, Part, SolutionWriter, SolverError};
+use serde::{Deserialize, Serialize};
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// The answers for both parts of a single problem.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    #[must_use]
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, part: Part, answer: String) {
+        match part {
+            Part::One => self.part1 = Some(answer),
+            Part::Two => self.part2 = Some(answer),
+        }
+    }
+}
+
+/// A database of expected answers, keyed by input spec.
+///
+/// The store is saved as a JSON object mapping `yyyy.dd:variant` to the
+/// answers of each part, so that it can be checked in and diffed.
+#[derive(Debug, Clone, Default)]
+pub struct AnswerStore {
+    answers: BTreeMap<Spec, Answers>,
+}
+
+impl AnswerStore {
+    /// Loads the store from the given path, returning an empty store if the
+    /// file doesn't exist.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read answers from {}", path.display()))?;
+        let raw: BTreeMap<String, Answers> = serde_json::from_str(&content)
+            .with_context(|| format!("invalid answers file {}", path.display()))?;
+        let answers = raw
+            .into_iter()
+            .map(|(spec, answers)| Ok((spec.parse()?, answers)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { answers })
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        std::fs::write(path, content)
+            .with_context(|| format!("failed to write answers to {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn get(&self, spec: &Spec) -> Option<&Answers> {
+        self.answers.get(spec)
+    }
+
+    /// Updates the answers for `spec`. Parts without an answer in `answers`
+    /// keep their previously recorded value.
+    pub fn record(&mut self, spec: &Spec, answers: &Answers) {
+        let entry = self.answers.entry(spec.clone()).or_default();
+        for part in [Part::One, Part::Two] {
+            if let Some(answer) = answers.get(part) {
+                entry.set(part, answer.to_owned());
+            }
+        }
+    }
+}
+
+impl Serialize for AnswerStore {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Keep the specs sorted by (year, day, variant) instead of sorting them as
+        // strings
+        serializer.collect_map(
+            self.answers
+                .iter()
+                .map(|(spec, answers)| (spec.to_string(), answers)),
+        )
+    }
+}
+
+/// The outcome of comparing a computed answer with the expected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+        actual: Option<String>,
+    },
+    Missing,
+    /// The part wasn't solved (e.g. because a filter selected the other part).
+    Skipped,
+}
+
+impl Verdict {
+    #[must_use]
+    pub fn new(expected: Option<&str>, actual: Option<&str>) -> Self {
+        match (expected, actual) {
+            (None, _) => Self::Missing,
+            (Some(expected), Some(actual)) if expected == actual => Self::Pass,
+            (Some(expected), actual) => Self::Fail {
+                expected: expected.to_owned(),
+                actual: actual.map(str::to_owned),
+            },
+        }
+    }
+}
+
+/// Compares the answers computed for each problem with an [`AnswerStore`]
+/// and prints a pass/fail/missing report.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    results: Vec<(Spec, [Verdict; 2])>,
+}
+
+impl CheckReport {
+    /// Adds the verdicts of a problem, where `solved` is the part that was
+    /// solved, or `None` if both parts were.
+    pub fn add(
+        &mut self,
+        spec: &Spec,
+        solved: Option<Part>,
+        expected: Option<&Answers>,
+        actual: &Answers,
+    ) {
+        let verdict = |part| {
+            if solved.is_some_and(|solved| solved != part) {
+                Verdict::Skipped
+            } else {
+                Verdict::new(expected.and_then(|e| e.get(part)), actual.get(part))
+            }
+        };
+        self.results
+            .push((spec.clone(), [verdict(Part::One), verdict(Part::Two)]));
+    }
+
+    fn count(&self, f: impl Fn(&Verdict) -> bool) -> usize {
+        self.results
+            .iter()
+            .flat_map(|(_, verdicts)| verdicts)
+            .filter(|v| f(v))
+            .count()
+    }
+
+    #[must_use]
+    pub fn failures(&self) -> usize {
+        self.count(|v| matches!(v, Verdict::Fail { .. }))
+    }
+
+    pub fn print(&self, stdout: &mut impl WriteColor) -> std::io::Result<()> {
+        stdout.set_color(ColorSpec::new().set_bold(true))?;
+        writeln!(stdout, "Check results")?;
+        stdout.reset()?;
+
+        for (spec, verdicts) in &self.results {
+            write!(stdout, "    {:<20}", spec.to_string())?;
+            for (part, verdict) in [Part::One, Part::Two].into_iter().zip(verdicts) {
+                write!(stdout, "  [part {part}] ")?;
+                match verdict {
+                    Verdict::Pass => {
+                        stdout.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
+                        write!(stdout, "ok")?;
+                    }
+                    Verdict::Fail { .. } => {
+                        stdout.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+                        write!(stdout, "FAILED")?;
+                    }
+                    Verdict::Missing => {
+                        stdout.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+                        write!(stdout, "missing")?;
+                    }
+                    Verdict::Skipped => {
+                        stdout.set_color(ColorSpec::new().set_dimmed(true))?;
+                        write!(stdout, "skipped")?;
+                    }
+                }
+                stdout.reset()?;
+            }
+            writeln!(stdout)?;
+
+            for (part, verdict) in [Part::One, Part::Two].into_iter().zip(verdicts) {
+                if let Verdict::Fail { expected, actual } = verdict {
+                    stdout.set_color(ColorSpec::new().set_dimmed(true))?;
+                    let actual = actual.as_deref().unwrap_or("<no answer>");
+                    writeln!(
+                        stdout,
+                        "        part {part}: expected {expected:?}, got {actual:?}"
+                    )?;
+                    stdout.reset()?;
+                }
+            }
+        }
+
+        let passed = self.count(|v| matches!(v, Verdict::Pass));
+        let missing = self.count(|v| matches!(v, Verdict::Missing));
+        writeln!(
+            stdout,
+            "{passed} passed, {} failed, {missing} missing",
+            self.failures()
+        )?;
+        Ok(())
+    }
+}
+
+/// The answers and timings of a single problem, as seen by a [`Recorder`].
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    pub answers: Answers,
+    pub stats: [Option<Stats>; 2],
+    /// The number of solutions that didn't match the expected answers given by
+    /// the input.
+    pub mismatches: usize,
+}
+
+/// A [`SolutionWriter`] that forwards everything to another writer while
+/// keeping a copy of the answers and their timings.
+pub struct Recorder<W: SolutionWriter> {
+    inner: W,
+    recording: Recording,
+}
+
+impl<W: SolutionWriter> Recorder<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            recording: Recording::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn into_recording(self) -> Recording {
+        self.recording
+    }
+}
+
+impl<W: SolutionWriter> SolutionWriter for Recorder<W> {
+    fn write_heading(&mut self, spec: &Spec) -> aoc::Result<()> {
+        self.inner.write_heading(spec)
+    }
+
+    fn write_solution(
+        &mut self,
+        part: Part,
+        stats: &Stats,
+        solution: &dyn Display,
+    ) -> aoc::Result<()> {
+        self.recording.answers.set(part, solution.to_string());
+        self.recording.stats[part.to_index()] = Some(*stats);
+        self.inner.write_solution(part, stats, solution)
+    }
+
+    fn write_error(&mut self, err: &SolverError) -> aoc::Result<()> {
+        self.inner.write_error(err)
+    }
+
+    fn write_mismatch(&mut self, part: Part, expected: &str, actual: &str) -> aoc::Result<()> {
+        self.recording.mismatches += 1;
+        self.inner.write_mismatch(part, expected, actual)
+    }
+
+    fn write_warning(&mut self, msg: &dyn Display) -> aoc::Result<()> {
+        self.inner.write_warning(msg)
+    }
+
+    fn write_phases(
+        &mut self,
+        phases: &[(&str, Stats)],
+        parts: [Option<Stats>; 2],
+    ) -> aoc::Result<()> {
+        self.inner.write_phases(phases, parts)
+    }
+
+    fn write_footer(&mut self, dropped_time: Duration) -> aoc::Result<()> {
+        self.inner.write_footer(dropped_time)
+    }
+
+    fn finish(&mut self) -> aoc::Result<()> {
+        self.inner.finish()
+    }
+}
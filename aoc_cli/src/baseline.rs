@@ -0,0 +1,244 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use aoc::{
+    input::Spec,
+    stats::{self, Stats},
+    Part,
+};
+use serde::{Deserialize, Serialize};
+use termcolor::{Color, ColorSpec, WriteColor};
+
+use crate::json_writer::StatsRecord;
+
+/// Changes with a p-value above this are considered noise.
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PartStats {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    part1: Option<StatsRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    part2: Option<StatsRecord>,
+}
+
+/// The timings of a previous run, saved with `run --save-baseline NAME`.
+#[derive(Debug, Clone, Default)]
+pub struct Baseline {
+    stats: BTreeMap<Spec, PartStats>,
+}
+
+impl Baseline {
+    fn path(dir: &Path, name: &str) -> PathBuf {
+        dir.join(format!("{name}.json"))
+    }
+
+    pub fn load(dir: &Path, name: &str) -> anyhow::Result<Self> {
+        let path = Self::path(dir, name);
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read baseline from {}", path.display()))?;
+        let raw: BTreeMap<String, PartStats> = serde_json::from_str(&content)
+            .with_context(|| format!("invalid baseline file {}", path.display()))?;
+        let stats = raw
+            .into_iter()
+            .map(|(spec, stats)| Ok((spec.parse()?, stats)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { stats })
+    }
+
+    /// Returns the name of the most recently saved baseline in `dir`, if
+    /// there is any.
+    pub fn latest(dir: &Path) -> anyhow::Result<Option<String>> {
+        if !dir.exists() {
+            return Ok(None);
+        }
+        let entries = std::fs::read_dir(dir)
+            .with_context(|| format!("failed to read baselines from {}", dir.display()))?;
+        let mut latest = None;
+        for entry in entries {
+            let path = entry?.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|name| name.to_str()?.strip_suffix(".json"))
+            else {
+                continue;
+            };
+            let modified = path.metadata()?.modified()?;
+            if latest.as_ref().is_none_or(|(time, _)| modified > *time) {
+                latest = Some((modified, name.to_owned()));
+            }
+        }
+        Ok(latest.map(|(_, name)| name))
+    }
+
+    /// Loads the baseline, returning an empty baseline if it doesn't exist.
+    pub fn load_or_default(dir: &Path, name: &str) -> anyhow::Result<Self> {
+        if Self::path(dir, name).exists() {
+            Self::load(dir, name)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, dir: &Path, name: &str) -> anyhow::Result<()> {
+        let path = Self::path(dir, name);
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create directory {}", dir.display()))?;
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("failed to write baseline to {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn get(&self, spec: &Spec, part: Part) -> Option<Stats> {
+        let stats = self.stats.get(spec)?;
+        match part {
+            Part::One => stats.part1.as_ref(),
+            Part::Two => stats.part2.as_ref(),
+        }
+        .map(Stats::from)
+    }
+
+    /// Updates the timings of `spec`. Parts that weren't run keep their
+    /// previous timings.
+    pub fn record(&mut self, spec: &Spec, stats: &[Option<Stats>; 2]) {
+        let entry = self.stats.entry(spec.clone()).or_default();
+        if let Some(stats) = &stats[Part::One.to_index()] {
+            entry.part1 = Some(stats.into());
+        }
+        if let Some(stats) = &stats[Part::Two.to_index()] {
+            entry.part2 = Some(stats.into());
+        }
+    }
+}
+
+impl Serialize for Baseline {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.stats
+                .iter()
+                .map(|(spec, stats)| (spec.to_string(), stats)),
+        )
+    }
+}
+
+/// How the timing of a problem part changed with respect to the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Change {
+    Regressed,
+    Improved,
+    Unchanged,
+}
+
+struct Row {
+    spec: Spec,
+    part: Part,
+    old: Stats,
+    new: Stats,
+    relative_change: f64,
+    p_value: Option<f64>,
+    change: Change,
+}
+
+/// Compares timings with a [`Baseline`] and prints a report of the changes.
+pub struct Comparison {
+    threshold: f64,
+    rows: Vec<Row>,
+}
+
+impl Comparison {
+    /// Creates an empty comparison. Changes larger than `threshold` (relative
+    /// to the baseline mean) are flagged if they are statistically significant.
+    #[must_use]
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, spec: &Spec, baseline: &Baseline, stats: &[Option<Stats>; 2]) {
+        for part in [Part::One, Part::Two] {
+            let (Some(old), Some(new)) = (baseline.get(spec, part), stats[part.to_index()]) else {
+                continue;
+            };
+            let old_mean = old.exec_time_mean.as_secs_f64();
+            let new_mean = new.exec_time_mean.as_secs_f64();
+            let relative_change = if old_mean == 0.0 {
+                0.0
+            } else {
+                (new_mean - old_mean) / old_mean
+            };
+            // Without a std (single runs) we can't test significance, so we
+            // rely on the threshold alone
+            let p_value = stats::welch_t_test(&old, &new);
+            let significant = p_value.is_none_or(|p| p < SIGNIFICANCE_LEVEL);
+            let change = if significant && relative_change > self.threshold {
+                Change::Regressed
+            } else if significant && relative_change < -self.threshold {
+                Change::Improved
+            } else {
+                Change::Unchanged
+            };
+            self.rows.push(Row {
+                spec: spec.clone(),
+                part,
+                old,
+                new,
+                relative_change,
+                p_value,
+                change,
+            });
+        }
+    }
+
+    pub fn print(&self, name: &str, stdout: &mut impl WriteColor) -> std::io::Result<()> {
+        stdout.set_color(ColorSpec::new().set_bold(true))?;
+        writeln!(stdout, "Comparison with baseline \"{name}\"")?;
+        stdout.reset()?;
+
+        for row in &self.rows {
+            write!(
+                stdout,
+                "    {:<20}  [part {}] {:>10.1?} -> {:<10.1?} {:>+7.1}%",
+                row.spec.to_string(),
+                row.part,
+                row.old.exec_time_mean,
+                row.new.exec_time_mean,
+                row.relative_change * 100.0,
+            )?;
+            match row.p_value {
+                Some(p) => write!(stdout, "  (p = {p:.3})")?,
+                None => write!(stdout, "  (p = n/a)")?,
+            }
+            match row.change {
+                Change::Regressed => {
+                    stdout.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+                    write!(stdout, "  regressed")?;
+                }
+                Change::Improved => {
+                    stdout.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
+                    write!(stdout, "  improved")?;
+                }
+                Change::Unchanged => {}
+            }
+            stdout.reset()?;
+            writeln!(stdout)?;
+        }
+
+        let regressions = self
+            .rows
+            .iter()
+            .filter(|row| row.change == Change::Regressed)
+            .count();
+        writeln!(
+            stdout,
+            "{regressions} regression(s) beyond {:.1}%",
+            self.threshold * 100.0
+        )?;
+        Ok(())
+    }
+}
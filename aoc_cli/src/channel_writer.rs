@@ -0,0 +1,131 @@
+use std::{fmt::Display, io, sync::mpsc, time::Duration};
+
+use aoc::{input::Spec, stats::Stats, Part, SolutionWriter, SolverError};
+
+/// A call made to a [`SolutionWriter`], recorded so that it can be sent to
+/// another thread and replayed there.
+#[derive(Debug)]
+pub enum Event {
+    Heading(Spec),
+    Solution {
+        part: Part,
+        stats: Stats,
+        solution: String,
+    },
+    Error(SolverError),
+    Mismatch {
+        part: Part,
+        expected: String,
+        actual: String,
+    },
+    Warning(String),
+    Phases {
+        phases: Vec<(String, Stats)>,
+        parts: [Option<Stats>; 2],
+    },
+    Footer(Duration),
+    /// The problem couldn't be run (e.g. there was no solver for it).
+    Abort(anyhow::Error),
+}
+
+impl Event {
+    pub fn replay(self, writer: &mut dyn SolutionWriter) -> anyhow::Result<()> {
+        match self {
+            Event::Heading(spec) => writer.write_heading(&spec)?,
+            Event::Solution {
+                part,
+                stats,
+                solution,
+            } => writer.write_solution(part, &stats, &solution)?,
+            Event::Error(err) => writer.write_error(&err)?,
+            Event::Mismatch {
+                part,
+                expected,
+                actual,
+            } => writer.write_mismatch(part, &expected, &actual)?,
+            Event::Warning(msg) => writer.write_warning(&msg)?,
+            Event::Phases { phases, parts } => {
+                let phases = phases
+                    .iter()
+                    .map(|(name, stats)| (name.as_str(), *stats))
+                    .collect::<Vec<_>>();
+                writer.write_phases(&phases, parts)?;
+            }
+            Event::Footer(dropped_time) => writer.write_footer(dropped_time)?,
+            Event::Abort(err) => return Err(err),
+        }
+        Ok(())
+    }
+}
+
+/// A [`SolutionWriter`] that sends every call through a channel, so that
+/// problems can be solved in worker threads while a single thread writes
+/// the output.
+pub struct ChannelWriter {
+    sender: mpsc::Sender<Event>,
+}
+
+impl ChannelWriter {
+    #[must_use]
+    pub fn new(sender: mpsc::Sender<Event>) -> Self {
+        Self { sender }
+    }
+
+    pub fn send(&self, event: Event) -> aoc::Result<()> {
+        self.sender
+            .send(event)
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "output channel closed").into())
+    }
+}
+
+impl SolutionWriter for ChannelWriter {
+    fn write_heading(&mut self, spec: &Spec) -> aoc::Result<()> {
+        self.send(Event::Heading(spec.clone()))
+    }
+
+    fn write_solution(
+        &mut self,
+        part: Part,
+        stats: &Stats,
+        solution: &dyn Display,
+    ) -> aoc::Result<()> {
+        self.send(Event::Solution {
+            part,
+            stats: *stats,
+            solution: solution.to_string(),
+        })
+    }
+
+    fn write_error(&mut self, err: &SolverError) -> aoc::Result<()> {
+        // SolverError isn't Clone, so we send an equivalent error instead
+        self.send(Event::Error(err.duplicate()))
+    }
+
+    fn write_mismatch(&mut self, part: Part, expected: &str, actual: &str) -> aoc::Result<()> {
+        self.send(Event::Mismatch {
+            part,
+            expected: expected.to_owned(),
+            actual: actual.to_owned(),
+        })
+    }
+
+    fn write_warning(&mut self, msg: &dyn Display) -> aoc::Result<()> {
+        self.send(Event::Warning(msg.to_string()))
+    }
+
+    fn write_phases(
+        &mut self,
+        phases: &[(&str, Stats)],
+        parts: [Option<Stats>; 2],
+    ) -> aoc::Result<()> {
+        let phases = phases
+            .iter()
+            .map(|&(name, stats)| (name.to_owned(), stats))
+            .collect();
+        self.send(Event::Phases { phases, parts })
+    }
+
+    fn write_footer(&mut self, dropped_time: Duration) -> aoc::Result<()> {
+        self.send(Event::Footer(dropped_time))
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context};
+use clap::Args;
+
+use crate::run::parse_duration_s;
+
+const USER_AGENT: &str = concat!(
+    "github.com/fernandokm/adventofcode-rust by ",
+    env!("CARGO_PKG_AUTHORS")
+);
+
+/// Options shared by the commands that talk to the Advent of Code website.
+#[derive(Debug, Args)]
+pub struct ClientArgs {
+    #[clap(
+        long,
+        env = "AOC_BASE_URL",
+        default_value = "https://adventofcode.com",
+        help = "The Advent of Code website (can be replaced by a local server for testing)"
+    )]
+    base_url: String,
+
+    #[clap(
+        long,
+        env = "AOC_SESSION",
+        hide_env_values = true,
+        help = "The session cookie used to authenticate (overrides --session-file)"
+    )]
+    session: Option<String>,
+
+    #[clap(
+        long,
+        default_value = ".aoc-session",
+        help = "A file containing the session cookie used to authenticate"
+    )]
+    session_file: PathBuf,
+
+    #[clap(
+        long,
+        value_parser = parse_duration_s,
+        default_value = "5",
+        help = "Wait at least this many seconds between consecutive requests"
+    )]
+    min_interval_s: Duration,
+
+    #[clap(
+        long,
+        default_value = ".aoc",
+        help = "The directory where local state (e.g. the time of the last request) is kept"
+    )]
+    pub state_dir: PathBuf,
+}
+
+/// A minimal client for the Advent of Code website.
+pub struct Client {
+    base_url: String,
+    session: String,
+    min_interval: Duration,
+    last_request_file: PathBuf,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(args: &ClientArgs) -> anyhow::Result<Self> {
+        let session = match &args.session {
+            Some(session) => session.clone(),
+            None => std::fs::read_to_string(&args.session_file)
+                .with_context(|| {
+                    format!(
+                        "no session cookie found (set AOC_SESSION or write it to {})",
+                        args.session_file.display()
+                    )
+                })?
+                .trim()
+                .to_owned(),
+        };
+        Ok(Self {
+            base_url: args.base_url.trim_end_matches('/').to_owned(),
+            session,
+            min_interval: args.min_interval_s,
+            last_request_file: args.state_dir.join("last-request"),
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+        })
+    }
+
+    pub fn get(&self, path: &str) -> anyhow::Result<String> {
+        self.throttle()?;
+        let url = format!("{}{path}", self.base_url);
+        let request = self.agent.get(&url);
+        Self::read_response(&url, self.authenticate(request).call())
+    }
+
+    pub fn post_form(&self, path: &str, fields: &[(&str, &str)]) -> anyhow::Result<String> {
+        self.throttle()?;
+        let url = format!("{}{path}", self.base_url);
+        let request = self.agent.post(&url);
+        Self::read_response(&url, self.authenticate(request).send_form(fields))
+    }
+
+    fn authenticate(&self, request: ureq::Request) -> ureq::Request {
+        request.set("Cookie", &format!("session={}", self.session))
+    }
+
+    fn read_response(
+        url: &str,
+        response: Result<ureq::Response, ureq::Error>,
+    ) -> anyhow::Result<String> {
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(code, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                bail!(
+                    "request to {url} failed with status {code}: {}",
+                    body.trim()
+                )
+            }
+            Err(e) => Err(anyhow!(e).context(format!("request to {url} failed"))),
+        }
+    }
+
+    /// Waits until at least `min_interval` has passed since the last request
+    /// (including requests made by previous invocations).
+    fn throttle(&self) -> anyhow::Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        if let Some(last) = Self::read_last_request(&self.last_request_file) {
+            let wait = (last + self.min_interval).saturating_sub(now);
+            if !wait.is_zero() {
+                eprintln!("Waiting {wait:.1?} before the next request...");
+                std::thread::sleep(wait);
+            }
+        }
+
+        if let Some(dir) = self.last_request_file.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create directory {}", dir.display()))?;
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        std::fs::write(&self.last_request_file, now.as_secs_f64().to_string())
+            .with_context(|| format!("failed to write {}", self.last_request_file.display()))
+    }
+
+    fn read_last_request(path: &Path) -> Option<Duration> {
+        let content = std::fs::read_to_string(path).ok()?;
+        Duration::try_from_secs_f64(content.trim().parse().ok()?).ok()
+    }
+}
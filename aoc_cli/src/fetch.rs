@@ -0,0 +1,164 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use aoc::{
+    input::{self, crypto::Key, Spec},
+    ProblemId,
+};
+use clap::Args;
+use regex::Regex;
+
+use crate::{
+    client::{Client, ClientArgs},
+    run::ProblemFilter,
+};
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    #[clap(
+        name = "problem",
+        help = "The problem to download, in the format yyyy.dd"
+    )]
+    problem: ProblemFilter,
+
+    #[clap(long, help = "Download the files again even if they already exist")]
+    force: bool,
+
+    #[clap(
+        long,
+        default_value = "inputs",
+        help = "The directory where inputs are saved"
+    )]
+    inputs_dir: PathBuf,
+
+    #[clap(
+        long,
+        default_value = "puzzles",
+        help = "The directory where puzzle descriptions are saved"
+    )]
+    puzzles_dir: PathBuf,
+
+    #[clap(flatten)]
+    client: ClientArgs,
+}
+
+impl Cmd {
+    pub fn exec(&self) -> anyhow::Result<()> {
+        let id = self.problem.problem_id()?;
+        let spec = Spec {
+            id,
+            variant: "real".to_owned(),
+        };
+        let existing_inputs = self.existing_inputs(&spec)?;
+        let puzzle_path = self
+            .puzzles_dir
+            .join(id.year.to_string())
+            .join(format!("day{:02}.html", id.day));
+
+        let fetch_input = self.should_download(existing_inputs.first());
+        let fetch_puzzle = self.should_download(Some(&puzzle_path).filter(|path| path.exists()));
+        if !fetch_input && !fetch_puzzle {
+            return Ok(());
+        }
+        let client = Client::new(&self.client)?;
+
+        if fetch_input {
+            // Inputs are encrypted as soon as a key is configured, so that
+            // they are never saved in plain text
+            let key = Key::load()?;
+            let input = client.get(&format!("/{}/day/{}/input", id.year, id.day))?;
+            let (input_path, content) = match key {
+                Some(key) => (
+                    self.input_path(id)?.with_extension("txt.enc"),
+                    key.encrypt(input.as_bytes()),
+                ),
+                None => (self.input_path(id)?, input.into_bytes()),
+            };
+            write_file(&input_path, &content)?;
+            println!("Saved input to {}", input_path.display());
+
+            // Other copies of the input would be duplicates of the new one
+            for path in existing_inputs.iter().filter(|path| **path != input_path) {
+                std::fs::remove_file(path)
+                    .with_context(|| format!("failed to remove {}", path.display()))?;
+                println!("Removed {}", path.display());
+            }
+        }
+
+        if fetch_puzzle {
+            let page = client.get(&format!("/{}/day/{}", id.year, id.day))?;
+            let description = extract_description(&page)?;
+            write_file(&puzzle_path, description.as_bytes())?;
+            println!("Saved puzzle description to {}", puzzle_path.display());
+        }
+
+        Ok(())
+    }
+
+    fn input_path(&self, id: ProblemId) -> anyhow::Result<PathBuf> {
+        let path = self
+            .inputs_dir
+            .join(id.year.to_string())
+            .join(format!("day{:02}_real.txt", id.day));
+        // Make sure the file will be picked up by the input sources
+        input::parse_input_filename(&path.to_string_lossy())?;
+        Ok(path)
+    }
+
+    /// Finds the files containing the input of `spec`, whether they are
+    /// compressed or encrypted.
+    fn existing_inputs(&self, spec: &Spec) -> anyhow::Result<Vec<PathBuf>> {
+        let dir = self.inputs_dir.join(spec.id.year.to_string());
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+        };
+        let mut paths = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if input::parse_input_filename(&path.to_string_lossy()).is_ok_and(|s| s == *spec) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Checks whether a file should be downloaded, given the path of its
+    /// existing copy (if any).
+    fn should_download(&self, existing: Option<&PathBuf>) -> bool {
+        match existing {
+            Some(path) if !self.force => {
+                println!(
+                    "{} already exists, skipping (use --force to download it again)",
+                    path.display()
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Extracts the puzzle description (one `<article>` per part) from a puzzle
+/// page.
+fn extract_description(page: &str) -> anyhow::Result<String> {
+    let article_re = Regex::new(r#"(?s)<article class="day-desc">.*?</article>"#).unwrap();
+    let articles: Vec<_> = article_re.find_iter(page).map(|m| m.as_str()).collect();
+    if articles.is_empty() {
+        bail!("no puzzle description found in the puzzle page");
+    }
+    Ok(articles.join("\n") + "\n")
+}
+
+fn write_file(path: &Path, content: &[u8]) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create directory {}", dir.display()))?;
+    }
+    std::fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use aoc::input::{self, crypto::Key};
+use clap::{Args, Subcommand};
+
+#[derive(Debug, Subcommand)]
+pub enum Cmd {
+    #[clap(about = "Encrypt input files (replacing dayDD_variant.txt with dayDD_variant.txt.enc)")]
+    Encrypt(Files),
+
+    #[clap(about = "Decrypt encrypted input files")]
+    Decrypt(Files),
+
+    #[clap(about = "Generate a new key and save it to the key file")]
+    Keygen,
+}
+
+#[derive(Debug, Args)]
+pub struct Files {
+    #[clap(
+        default_value = "inputs",
+        help = "The input files to process (directories are searched recursively)"
+    )]
+    paths: Vec<PathBuf>,
+
+    #[clap(long, help = "Keep the original files")]
+    keep: bool,
+}
+
+impl Cmd {
+    pub fn exec(&self) -> anyhow::Result<()> {
+        match self {
+            Cmd::Encrypt(files) => {
+                let key = load_key()?;
+                files.process(|path| {
+                    let name = path.to_string_lossy();
+                    if name.ends_with(".enc") || input::parse_input_filename(&name).is_err() {
+                        return Ok(None);
+                    }
+                    let data = read(path)?;
+                    Ok(Some((format!("{name}.enc").into(), key.encrypt(&data))))
+                })
+            }
+            Cmd::Decrypt(files) => {
+                let key = load_key()?;
+                files.process(|path| {
+                    let name = path.to_string_lossy();
+                    let Some(decrypted_name) = name.strip_suffix(".enc") else {
+                        return Ok(None);
+                    };
+                    let data = key.decrypt(&name, &read(path)?)?;
+                    Ok(Some((decrypted_name.into(), data)))
+                })
+            }
+            Cmd::Keygen => {
+                let path = Key::file_path();
+                if path.exists() {
+                    bail!("{} already exists", path.display());
+                }
+                std::fs::write(&path, Key::generate().to_hex() + "\n")
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+                println!(
+                    "Saved a new key to {} (keep it out of version control)",
+                    path.display()
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Files {
+    /// Converts every file for which `f` returns a new path and contents,
+    /// removing the original file unless `--keep` was given.
+    fn process(
+        &self,
+        mut f: impl FnMut(&Path) -> anyhow::Result<Option<(PathBuf, Vec<u8>)>>,
+    ) -> anyhow::Result<()> {
+        let mut files = Vec::new();
+        for path in &self.paths {
+            find_files(path, &mut files)?;
+        }
+        files.sort();
+
+        for path in files {
+            let Some((new_path, content)) = f(&path)? else {
+                continue;
+            };
+            if new_path.exists() {
+                bail!("{} already exists", new_path.display());
+            }
+            std::fs::write(&new_path, content)
+                .with_context(|| format!("failed to write {}", new_path.display()))?;
+            if !self.keep {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("failed to remove {}", path.display()))?;
+            }
+            println!("{} -> {}", path.display(), new_path.display());
+        }
+        Ok(())
+    }
+}
+
+fn load_key() -> anyhow::Result<Key> {
+    match Key::load()? {
+        Some(key) => Ok(key),
+        None => bail!(
+            "no key found (set {} or run `inputs keygen` to create {})",
+            input::crypto::KEY_ENV_VAR,
+            Key::file_path().display()
+        ),
+    }
+}
+
+fn find_files(path: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let entries =
+        std::fs::read_dir(path).with_context(|| format!("failed to read {}", path.display()))?;
+    for entry in entries {
+        find_files(&entry?.path(), files)?;
+    }
+    Ok(())
+}
+
+fn read(path: &Path) -> anyhow::Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+}
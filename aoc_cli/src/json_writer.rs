@@ -0,0 +1,212 @@
+use std::{
+    fmt::Display,
+    io::{self, Write},
+    time::Duration,
+};
+
+use aoc::{alloc::MemoryStats, input::Spec, stats::Stats, Part, SolutionWriter, SolverError};
+use serde::{Deserialize, Serialize};
+
+/// A [`SolutionWriter`] that writes one JSON record per problem part.
+///
+/// Records are written as a single JSON array once all problems have been
+/// solved or, with `lines` set, as one JSON object per line as soon as each
+/// problem finishes.
+#[derive(Debug, Default)]
+pub struct JsonWriter {
+    lines: bool,
+    current: Option<Spec>,
+    pending: Vec<Record>,
+    finished: Vec<Record>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Record {
+    pub year: u32,
+    pub day: u32,
+    pub variant: String,
+    pub part: Option<usize>,
+    pub phase: Option<String>,
+    pub solution: Option<String>,
+    /// The expected answer, if the solution didn't match it.
+    pub expected: Option<String>,
+    pub stats: Option<StatsRecord>,
+    pub dropped_time_ns: u128,
+    pub error: Option<String>,
+}
+
+/// The serialized form of [`Stats`] (all times are in nanoseconds). The
+/// memory fields are only set if allocations are tracked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct StatsRecord {
+    count: usize,
+    mean_ns: u128,
+    std_ns: Option<u128>,
+    total_ns: u128,
+    min_ns: u128,
+    max_ns: u128,
+    median_ns: u128,
+    p5_ns: u128,
+    p95_ns: u128,
+    outliers: usize,
+    allocations: Option<u64>,
+    peak_bytes: Option<u64>,
+}
+
+impl From<&Stats> for StatsRecord {
+    fn from(stats: &Stats) -> Self {
+        Self {
+            count: stats.exec_count,
+            mean_ns: stats.exec_time_mean.as_nanos(),
+            std_ns: stats.exec_time_std.map(|std| std.as_nanos()),
+            total_ns: stats.exec_time_total.as_nanos(),
+            min_ns: stats.exec_time_min.as_nanos(),
+            max_ns: stats.exec_time_max.as_nanos(),
+            median_ns: stats.exec_time_median.as_nanos(),
+            p5_ns: stats.exec_time_p5.as_nanos(),
+            p95_ns: stats.exec_time_p95.as_nanos(),
+            outliers: stats.outliers,
+            allocations: stats.memory.map(|m| m.allocations),
+            peak_bytes: stats.memory.map(|m| m.peak_bytes),
+        }
+    }
+}
+
+impl From<&StatsRecord> for Stats {
+    fn from(record: &StatsRecord) -> Self {
+        let nanos = |ns: u128| Duration::from_nanos(u64::try_from(ns).unwrap_or(u64::MAX));
+        Self {
+            exec_count: record.count,
+            exec_time_total: nanos(record.total_ns),
+            exec_time_mean: nanos(record.mean_ns),
+            exec_time_std: record.std_ns.map(nanos),
+            exec_time_min: nanos(record.min_ns),
+            exec_time_max: nanos(record.max_ns),
+            exec_time_median: nanos(record.median_ns),
+            exec_time_p5: nanos(record.p5_ns),
+            exec_time_p95: nanos(record.p95_ns),
+            outliers: record.outliers,
+            memory: record
+                .allocations
+                .zip(record.peak_bytes)
+                .map(|(allocations, peak_bytes)| MemoryStats {
+                    allocations,
+                    peak_bytes,
+                }),
+        }
+    }
+}
+
+impl JsonWriter {
+    #[must_use]
+    pub fn new(lines: bool) -> Self {
+        Self {
+            lines,
+            ..Self::default()
+        }
+    }
+
+    fn record(&self, part: Option<Part>) -> Record {
+        let spec = self
+            .current
+            .as_ref()
+            .expect("write_heading must be called before writing solutions");
+        Record {
+            year: spec.id.year,
+            day: spec.id.day,
+            variant: spec.variant.clone(),
+            part: part.map(|part| part.to_index() + 1),
+            phase: None,
+            solution: None,
+            expected: None,
+            stats: None,
+            dropped_time_ns: 0,
+            error: None,
+        }
+    }
+}
+
+impl SolutionWriter for JsonWriter {
+    fn write_heading(&mut self, spec: &Spec) -> aoc::Result<()> {
+        self.current = Some(spec.clone());
+        Ok(())
+    }
+
+    fn write_solution(
+        &mut self,
+        part: Part,
+        stats: &Stats,
+        solution: &dyn Display,
+    ) -> aoc::Result<()> {
+        let record = Record {
+            solution: Some(solution.to_string()),
+            stats: Some(stats.into()),
+            ..self.record(Some(part))
+        };
+        self.pending.push(record);
+        Ok(())
+    }
+
+    fn write_error(&mut self, err: &SolverError) -> aoc::Result<()> {
+        let record = Record {
+            error: Some(err.to_string()),
+            ..self.record(None)
+        };
+        self.pending.push(record);
+        Ok(())
+    }
+
+    fn write_mismatch(&mut self, part: Part, expected: &str, _actual: &str) -> aoc::Result<()> {
+        let part = Some(part.to_index() + 1);
+        if let Some(record) = self.pending.iter_mut().rev().find(|r| r.part == part) {
+            record.expected = Some(expected.to_owned());
+        }
+        Ok(())
+    }
+
+    fn write_phases(
+        &mut self,
+        phases: &[(&str, Stats)],
+        _parts: [Option<Stats>; 2],
+    ) -> aoc::Result<()> {
+        // Parts already have their own records
+        for (name, stats) in phases {
+            let record = Record {
+                phase: Some((*name).to_owned()),
+                stats: Some(stats.into()),
+                ..self.record(None)
+            };
+            self.pending.push(record);
+        }
+        Ok(())
+    }
+
+    fn write_warning(&mut self, msg: &dyn Display) -> aoc::Result<()> {
+        // stdout is reserved for the JSON output
+        eprintln!("{msg}");
+        Ok(())
+    }
+
+    fn write_footer(&mut self, dropped_time: Duration) -> aoc::Result<()> {
+        for mut record in self.pending.drain(..) {
+            record.dropped_time_ns = dropped_time.as_nanos();
+            if self.lines {
+                let mut stdout = io::stdout().lock();
+                serde_json::to_writer(&mut stdout, &record).map_err(io::Error::from)?;
+                writeln!(stdout)?;
+            } else {
+                self.finished.push(record);
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> aoc::Result<()> {
+        if !self.lines {
+            let mut stdout = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut stdout, &self.finished).map_err(io::Error::from)?;
+            writeln!(stdout)?;
+        }
+        Ok(())
+    }
+}
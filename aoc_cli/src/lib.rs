@@ -3,13 +3,25 @@
 // TODO: enable docs lints (remove the following line)
 #![allow(missing_docs, clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
+pub use answers::AnswerStore;
 use aoc::input;
 use clap::Parser;
+pub use json_writer::JsonWriter;
 pub use terminal_writer::TerminalWriter;
+mod answers;
+mod baseline;
+mod channel_writer;
+mod client;
+mod json_writer;
 mod terminal_writer;
 
+pub mod fetch;
+pub mod inputs;
 pub mod list;
+pub mod new;
 pub mod run;
+pub mod submit;
+pub mod watch;
 
 #[must_use]
 pub fn parse() -> AocApp {
@@ -20,13 +32,24 @@ pub fn parse() -> AocApp {
 pub enum AocApp {
     Run(run::Cmd),
     List(list::Cmd),
+    Fetch(fetch::Cmd),
+    Submit(submit::Cmd),
+    New(new::Cmd),
+    #[clap(subcommand)]
+    Inputs(inputs::Cmd),
+    Watch(watch::Cmd),
 }
 
 impl AocApp {
-    pub fn exec(&self, default_inputs: &impl input::Source) -> anyhow::Result<()> {
+    pub fn exec(&self, default_inputs: &(impl input::Source + Sync)) -> anyhow::Result<()> {
         match self {
             AocApp::Run(cmd) => cmd.exec(default_inputs),
             AocApp::List(cmd) => cmd.exec(default_inputs),
+            AocApp::Fetch(cmd) => cmd.exec(),
+            AocApp::Submit(cmd) => cmd.exec(default_inputs),
+            AocApp::New(cmd) => cmd.exec(),
+            AocApp::Inputs(cmd) => cmd.exec(),
+            AocApp::Watch(cmd) => cmd.exec(),
         }
     }
 }
@@ -1,79 +1,345 @@
-use aoc::{input, ProblemId, Solver};
+use std::{collections::BTreeSet, path::PathBuf, str::FromStr, sync::mpsc, thread, time::Duration};
+
+use aoc::{
+    input::{self, Spec},
+    Part, ProblemId, Solver, SolverError,
+};
 use clap::Args;
 use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{answers::AnswerStore, baseline::Baseline, run::parse_duration_s};
+
+const DAYS: u32 = 25;
 
 #[derive(Debug, Args)]
 pub struct Cmd {
     #[clap(
         short,
         long,
-        help = "Show detailed information for each problem (available input files and solvers)"
+        help = "Show detailed information for each problem (available input files)"
     )]
     verbose: bool,
 
     #[clap(short, long, help = "List only problems from the specified years")]
     year: Vec<u32>,
+
+    #[clap(
+        long,
+        conflicts_with = "format",
+        help = "Only list the days with a solver, as intervals"
+    )]
+    compact: bool,
+
+    #[clap(
+        long,
+        value_name = "inputs|solvers",
+        help = "List only the days with a solver but no real or test input (inputs), or the days \
+                without a solver (solvers), including those whose solver doesn't implement both \
+                parts with --run"
+    )]
+    missing: Option<Missing>,
+
+    #[clap(
+        long,
+        help = "Find out which parts each solver implements by running it on its first test input"
+    )]
+    run: bool,
+
+    #[clap(
+        long,
+        value_parser = parse_duration_s,
+        default_value = "5",
+        requires = "run",
+        help = "How long each solver may run with --run, in seconds"
+    )]
+    timeout: Duration,
+
+    #[clap(
+        short,
+        long,
+        default_value = "terminal",
+        help = "Controls the output format (terminal, json)"
+    )]
+    format: ListFormat,
+
+    #[clap(
+        long,
+        default_value = "answers.json",
+        help = "The expected answers database, used to show which parts have a recorded answer"
+    )]
+    answers: PathBuf,
+
+    #[clap(
+        long,
+        value_name = "NAME",
+        help = "The baseline the runtimes are taken from (defaults to the most recently saved one)"
+    )]
+    baseline: Option<String>,
+
+    #[clap(
+        long,
+        default_value = "target/baselines",
+        help = "The directory where baselines are stored"
+    )]
+    baseline_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Missing {
+    Inputs,
+    Solvers,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ListFormat {
+    Terminal,
+    Json,
+}
+
+/// The completion status of a single day.
+#[derive(Debug, Serialize)]
+struct DayStatus {
+    year: u32,
+    day: u32,
+    solver: bool,
+    /// Whether the solver implements each part, i.e. it answers the part
+    /// before returning [`SolverError::NotImplemented`] for its test input.
+    /// Solvers without a test input are assumed to implement both parts.
+    /// Only known with `--run`, if the test input could be solved in time.
+    implemented: Option<[bool; 2]>,
+    inputs: Vec<String>,
+    /// Whether the answer to each part of the real input was recorded in the
+    /// expected answers database.
+    recorded_answers: [bool; 2],
+    /// The runtime of both parts of the real input in the baseline.
+    runtime_ns: Option<u128>,
+}
+
+impl DayStatus {
+    fn has_input(&self, prefix: &str) -> bool {
+        self.inputs
+            .iter()
+            .any(|variant| variant.starts_with(prefix))
+    }
+
+    fn is_missing(&self, missing: Missing) -> bool {
+        match missing {
+            Missing::Inputs => self.solver && !(self.has_input("real") && self.has_input("test")),
+            Missing::Solvers => {
+                !self.solver || self.implemented.is_some_and(|parts| parts != [true, true])
+            }
+        }
+    }
 }
 
 impl Cmd {
     pub fn exec(&self, default_inputs: &impl input::Source) -> anyhow::Result<()> {
-        if self.verbose {
-            for (year, group) in &Solver::get_map().into_iter().group_by(|(id, _)| id.year) {
-                if !self.year.is_empty() && !self.year.contains(&year) {
-                    continue;
-                }
+        if self.compact {
+            self.print_compact();
+            return Ok(());
+        }
 
-                println!("[{}]", year);
-                for (id, _) in group {
-                    print!("  {:2}", id.day);
-
-                    Self::print_section(
-                        "inputs",
-                        32,
-                        default_inputs
-                            .keys()
-                            .into_iter()
-                            .filter(|spec| spec.id == id)
-                            .map(|spec| spec.variant.as_str()),
-                    );
-
-                    println!();
+        let statuses = self.statuses(default_inputs)?;
+        match self.format {
+            ListFormat::Terminal => self.print_grid(&statuses),
+            ListFormat::Json => println!("{}", serde_json::to_string_pretty(&statuses)?),
+        }
+        Ok(())
+    }
+
+    /// Returns the status of every day of the years with any solver or input.
+    fn statuses(&self, default_inputs: &impl input::Source) -> anyhow::Result<Vec<DayStatus>> {
+        let index = input::Index::new(default_inputs);
+        let solvers = Solver::get_map();
+        let answers = AnswerStore::load(&self.answers)?;
+        let baseline = match &self.baseline {
+            Some(name) => Some(Baseline::load(&self.baseline_dir, name)?),
+            None => Baseline::latest(&self.baseline_dir)?
+                .map(|name| Baseline::load(&self.baseline_dir, &name))
+                .transpose()?,
+        };
+
+        let years: BTreeSet<u32> = solvers
+            .keys()
+            .map(|id| id.year)
+            .chain(index.ids().map(|id| id.year))
+            .filter(|year| self.year.is_empty() || self.year.contains(year))
+            .collect();
+        let mut statuses = Vec::new();
+        for year in years {
+            for day in 1..=DAYS {
+                let id = ProblemId { year, day };
+                let real = Spec {
+                    id,
+                    variant: "real".to_owned(),
+                };
+                let runtime = baseline.as_ref().and_then(|baseline| {
+                    let parts = [Part::One, Part::Two].map(|part| baseline.get(&real, part));
+                    parts
+                        .iter()
+                        .flatten()
+                        .map(|s| s.exec_time_mean)
+                        .sum1::<Duration>()
+                });
+                let implemented = match solvers.get(&id) {
+                    Some(solver) if self.run => {
+                        implemented_parts(solver, &index, default_inputs, self.timeout)
+                    }
+                    _ => None,
+                };
+                let status = DayStatus {
+                    year,
+                    day,
+                    solver: solvers.contains_key(&id),
+                    implemented,
+                    inputs: index.by_id(id).map(|spec| spec.variant.clone()).collect(),
+                    recorded_answers: [Part::One, Part::Two].map(|part| {
+                        answers
+                            .get(&real)
+                            .is_some_and(|answers| answers.get(part).is_some())
+                    }),
+                    runtime_ns: runtime.map(|runtime| runtime.as_nanos()),
+                };
+                if self
+                    .missing
+                    .is_none_or(|missing| status.is_missing(missing))
+                {
+                    statuses.push(status);
                 }
             }
-        } else {
-            let intervals = get_intervals(Solver::get_map().keys().copied().sorted());
-            for (year, mut group) in &intervals.into_iter().group_by(|(id, _)| id.year) {
-                if !self.year.is_empty() && !self.year.contains(&year) {
-                    continue;
-                }
+        }
+        Ok(statuses)
+    }
 
-                print!("[{}]  ", year);
-                print_interval(group.next().unwrap());
-                for interval in group {
-                    print!(",  ");
-                    print_interval(interval);
+    fn print_grid(&self, statuses: &[DayStatus]) {
+        let mark = |value: bool| if value { "yes" } else { "-" };
+        for (year, group) in &statuses.iter().group_by(|status| status.year) {
+            println!("[{year}]");
+            print!("  day  solver   real  test  recorded  runtime");
+            if self.verbose {
+                print!("     inputs");
+            }
+            println!();
+            for status in group {
+                let solver = match status.implemented {
+                    _ if !status.solver => "-",
+                    None if self.run => "unknown",
+                    None | Some([true, true]) => "yes",
+                    Some([true, false]) => "part 1",
+                    Some([false, true]) => "part 2",
+                    Some([false, false]) => "stub",
+                };
+                let recorded = match status.recorded_answers {
+                    [true, true] => "1, 2",
+                    [true, false] => "1",
+                    [false, true] => "2",
+                    [false, false] => "-",
+                };
+                let runtime = status.runtime_ns.map_or_else(
+                    || "-".to_owned(),
+                    |ns| {
+                        format!(
+                            "{:.1?}",
+                            Duration::from_nanos(u64::try_from(ns).unwrap_or(u64::MAX))
+                        )
+                    },
+                );
+                print!(
+                    "  {:>3}  {:<7}  {:<4}  {:<4}  {:<8}",
+                    status.day,
+                    solver,
+                    mark(status.has_input("real")),
+                    mark(status.has_input("test")),
+                    recorded,
+                );
+                if self.verbose {
+                    print!("  {runtime:<9}  {}", status.inputs.join(", "));
+                } else {
+                    print!("  {runtime}");
                 }
                 println!();
             }
         }
+    }
 
-        Ok(())
+    fn print_compact(&self) {
+        let intervals = get_intervals(Solver::get_map().keys().copied().sorted());
+        for (year, mut group) in &intervals.into_iter().group_by(|(id, _)| id.year) {
+            if !self.year.is_empty() && !self.year.contains(&year) {
+                continue;
+            }
+
+            print!("[{}]  ", year);
+            print_interval(group.next().unwrap());
+            for interval in group {
+                print!(",  ");
+                print_interval(interval);
+            }
+            println!();
+        }
     }
+}
+
+impl FromStr for Missing {
+    type Err = anyhow::Error;
 
-    fn print_section<'a>(heading: &str, width: usize, values: impl Iterator<Item = &'a str> + 'a) {
-        let value_list = values
-            .into_iter()
-            .map(|s| if s.is_empty() { "<empty>" } else { s })
-            .join(", ");
-        print!(
-            "  [{}: {}]{}",
-            heading,
-            value_list,
-            " ".repeat(width.saturating_sub(6 + heading.len() + value_list.len())),
-        );
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "inputs" => Self::Inputs,
+            "solvers" => Self::Solvers,
+            _ => anyhow::bail!("invalid value \"{}\" (must be inputs or solvers)", s.trim()),
+        })
     }
 }
 
+impl FromStr for ListFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "terminal" => Self::Terminal,
+            "json" => Self::Json,
+            _ => anyhow::bail!(
+                "invalid output format \"{}\" (must be terminal or json)",
+                s.trim()
+            ),
+        })
+    }
+}
+
+/// Finds out which parts `solver` implements by solving its first test
+/// input (solvers created by `new` return [`SolverError::NotImplemented`]
+/// until they are implemented). Returns `None` if the input can't be read or
+/// the solver doesn't finish within `timeout`.
+fn implemented_parts(
+    solver: &'static Solver,
+    index: &input::Index<'_>,
+    inputs: &impl input::Source,
+    timeout: Duration,
+) -> Option<[bool; 2]> {
+    let Some(spec) = index
+        .by_id(solver.problem_id)
+        .find(|spec| spec.variant.starts_with("test"))
+    else {
+        return Some([true, true]);
+    };
+    let content = inputs.get(spec).ok()?.to_string();
+    let (sender, receiver) = mpsc::channel();
+    // A solver that times out keeps running in the background
+    thread::spawn(move || {
+        let report = input::Input::parse(&content).map(|input| solver.run(&input));
+        sender.send(report).ok();
+    });
+    let report = receiver.recv_timeout(timeout).ok()?.ok()?;
+    Some(match report.error {
+        Some(SolverError::NotImplemented) => {
+            [Part::One, Part::Two].map(|part| report.answer(part).is_some())
+        }
+        _ => [true, true],
+    })
+}
+
 fn print_interval((start, end): (ProblemId, ProblemId)) {
     if start == end {
         print!("{}", start.day);
@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use aoc::ProblemId;
+use clap::Args;
+
+use crate::run::ProblemFilter;
+
+const SOLVER_TEMPLATE: &str = "\
+use aoc::ProblemOutput;
+
+aoc::register!(solve, {year}, {day});
+
+pub fn solve(input: &str, out: &mut ProblemOutput<'_>) -> anyhow::Result<()> {
+    let _ = (input, out);
+    aoc::not_implemented!()
+}
+";
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    #[clap(
+        name = "problem",
+        help = "The problem to create, in the format yyyy.dd"
+    )]
+    problem: ProblemFilter,
+
+    #[clap(
+        long,
+        default_value = "src",
+        help = "The directory containing the year modules"
+    )]
+    src_dir: PathBuf,
+
+    #[clap(
+        long,
+        default_value = "inputs",
+        help = "The directory where inputs are saved"
+    )]
+    inputs_dir: PathBuf,
+}
+
+impl Cmd {
+    pub fn exec(&self) -> anyhow::Result<()> {
+        let id = self.problem.problem_id()?;
+        let year_dir = self.src_dir.join(format!("year{}", id.year));
+        let solver_path = year_dir.join(format!("day{:02}.rs", id.day));
+        if solver_path.exists() {
+            bail!("{} already exists", solver_path.display());
+        }
+
+        // Compute every change before writing anything, so that a failed edit
+        // doesn't leave a half-created solver behind
+        let mut files = Vec::new();
+        let mod_path = year_dir.join("mod.rs");
+        if mod_path.exists() {
+            let content = read(&mod_path)?;
+            files.push((mod_path, add_day_module(&content, id.day)));
+        } else {
+            let main_path = self.src_dir.join("main.rs");
+            let content = read(&main_path)?;
+            files.push((main_path, add_year_module(&content, id.year)?));
+            files.push((mod_path, format!("pub mod day{:02};\n", id.day)));
+        }
+
+        let solver = SOLVER_TEMPLATE
+            .replace("{year}", &id.year.to_string())
+            .replace("{day}", &id.day.to_string());
+        files.push((solver_path, solver));
+
+        let test_input = self.test_input_path(id);
+        if !test_input.exists() {
+            files.push((test_input, String::new()));
+        }
+
+        for (path, content) in &files {
+            write(path, content)?;
+        }
+        Ok(())
+    }
+
+    fn test_input_path(&self, id: ProblemId) -> PathBuf {
+        self.inputs_dir
+            .join(id.year.to_string())
+            .join(format!("day{:02}_test.txt", id.day))
+    }
+}
+
+/// Adds `pub mod dayDD;` to the module list of a year, replacing the
+/// commented-out placeholder for that day if there is one.
+fn add_day_module(content: &str, day: u32) -> String {
+    let module = format!("pub mod day{day:02};");
+    let mut lines: Vec<_> = content.lines().map(str::to_owned).collect();
+
+    let placeholders = [
+        format!("// pub mod day{day};"),
+        format!("// pub mod day{day:02};"),
+    ];
+    if let Some(line) = lines
+        .iter_mut()
+        .find(|line| placeholders.contains(&line.trim().to_owned()))
+    {
+        *line = module;
+    } else {
+        // Keep the days sorted, assuming they are declared in order
+        let pos = lines
+            .iter()
+            .rposition(|line| parse_module_number(line, "day").is_some_and(|d| d < day))
+            .map_or(0, |i| i + 1);
+        lines.insert(pos, module);
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Adds `pub mod yearYYYY;` to `main.rs`, keeping the years sorted.
+fn add_year_module(content: &str, year: u32) -> anyhow::Result<String> {
+    let mut lines: Vec<_> = content.lines().map(str::to_owned).collect();
+    let Some(first) = lines
+        .iter()
+        .position(|line| parse_module_number(line, "year").is_some())
+    else {
+        bail!("couldn't find the year modules in main.rs");
+    };
+    let pos = lines
+        .iter()
+        .rposition(|line| parse_module_number(line, "year").is_some_and(|y| y < year))
+        .map_or(first, |i| i + 1);
+    lines.insert(pos, format!("pub mod year{year};"));
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Parses lines such as `pub mod day05;` (or commented-out placeholders such
+/// as `// pub mod day5;`), returning the number after `prefix`.
+fn parse_module_number(line: &str, prefix: &str) -> Option<u32> {
+    line.trim()
+        .trim_start_matches("//")
+        .trim()
+        .strip_prefix("pub mod ")?
+        .strip_prefix(prefix)?
+        .strip_suffix(';')?
+        .parse()
+        .ok()
+}
+
+fn read(path: &Path) -> anyhow::Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+fn write(path: &Path, content: &str) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create directory {}", dir.display()))?;
+    }
+    std::fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_numbers() {
+        assert_eq!(parse_module_number("pub mod day05;", "day"), Some(5));
+        assert_eq!(parse_module_number("    pub mod day12;", "day"), Some(12));
+        assert_eq!(parse_module_number("// pub mod day7;", "day"), Some(7));
+        assert_eq!(parse_module_number("pub mod year2022;", "year"), Some(2022));
+        assert_eq!(parse_module_number("pub mod year2022;", "day"), None);
+        assert_eq!(parse_module_number("pub mod day05", "day"), None);
+        assert_eq!(parse_module_number("mod day05;", "day"), None);
+        assert_eq!(parse_module_number("pub mod util;", "day"), None);
+    }
+
+    #[test]
+    fn days_are_added_in_order() {
+        let content = "pub mod day01;\npub mod day02;\n";
+        assert_eq!(
+            add_day_module(content, 3),
+            "pub mod day01;\npub mod day02;\npub mod day03;\n"
+        );
+        // Filling a gap
+        let content = "pub mod day01;\npub mod day04;\n";
+        assert_eq!(
+            add_day_module(content, 2),
+            "pub mod day01;\npub mod day02;\npub mod day04;\n"
+        );
+        assert_eq!(add_day_module("", 1), "pub mod day01;\n");
+    }
+
+    #[test]
+    fn day_placeholders_are_replaced() {
+        let content = "pub mod day01;\n// pub mod day2;\n// pub mod day03;\n";
+        assert_eq!(
+            add_day_module(content, 2),
+            "pub mod day01;\npub mod day02;\n// pub mod day03;\n"
+        );
+        assert_eq!(
+            add_day_module(content, 3),
+            "pub mod day01;\n// pub mod day2;\npub mod day03;\n"
+        );
+    }
+
+    #[test]
+    fn years_are_added_in_order() {
+        let content = "mod util;\n\npub mod year2020;\npub mod year2022;\n\nfn main() {}\n";
+        assert_eq!(
+            add_year_module(content, 2021).unwrap(),
+            "mod util;\n\npub mod year2020;\npub mod year2021;\npub mod year2022;\n\nfn \
+             main() {}\n"
+        );
+        assert_eq!(
+            add_year_module(content, 2019).unwrap(),
+            "mod util;\n\npub mod year2019;\npub mod year2020;\npub mod year2022;\n\nfn \
+             main() {}\n"
+        );
+        assert_eq!(
+            add_year_module(content, 2023).unwrap(),
+            "mod util;\n\npub mod year2020;\npub mod year2022;\npub mod year2023;\n\nfn \
+             main() {}\n"
+        );
+    }
+
+    #[test]
+    fn missing_year_modules() {
+        assert!(add_year_module("mod util;\n\nfn main() {}\n", 2022).is_err());
+    }
+}
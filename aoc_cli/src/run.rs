@@ -1,19 +1,41 @@
-use std::{str::FromStr, time::Duration};
+use std::{
+    collections::BTreeMap,
+    io::{self, Read},
+    num::NonZeroUsize,
+    ops::RangeInclusive,
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, bail, Context};
 use aoc::{
-    input::{self, Spec},
-    ProblemOutput, Solver,
+    input::{self, Source, Spec},
+    Part, ProblemId, ProblemOutput, SolutionWriter, Solver, SolverError,
 };
 use clap::Args;
 use itertools::Itertools;
-use rustc_hash::FxHashSet;
+use rustc_hash::FxHashMap;
+use termcolor::StandardStream;
 
-use crate::terminal_writer;
+use crate::{
+    answers::{AnswerStore, CheckReport, Recorder, Recording},
+    baseline::{Baseline, Comparison},
+    channel_writer::{ChannelWriter, Event},
+    json_writer::JsonWriter,
+    TerminalWriter,
+};
 
 const MAX_DROPPED_PERCENT: f64 = 0.25;
 
 #[derive(Debug, Args)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Cmd {
     #[clap(
         short = 'n',
@@ -32,13 +54,79 @@ pub struct Cmd {
     )]
     min_duration_s: Duration,
 
+    #[clap(
+        long,
+        help = "Run each solver this many extra times before measuring (implies benchmarking)",
+        default_value = "0"
+    )]
+    warmup: usize,
+
+    #[clap(
+        long,
+        help = "Show the min, max, median and percentiles of the execution times and the number \
+                of outliers"
+    )]
+    detailed_stats: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "Solve up to this many problems in parallel (0 uses one job per CPU); benchmarks \
+                always run serially",
+        default_value = "1"
+    )]
+    jobs: usize,
+
     #[clap(
         name = "problems",
-        help = "A list of problems to be solved, in the format yyyy[.dd][:variant] (ignored if \
-                --all is specified)"
+        help = "A list of problems to be solved, in the format [!]yyyy[.dd][:variant][/part], \
+                where years and days can be lists and ranges (2021.1,3,10-15), variants can \
+                contain * wildcards, and ! excludes problems (ignored if --all is specified)"
     )]
     problems_filters: Vec<ProblemFilter>,
 
+    #[clap(
+        long = "inputs",
+        value_name = "DIR",
+        help = "Read inputs from this directory (and its year subdirectories), overriding the \
+                embedded inputs"
+    )]
+    inputs_dirs: Vec<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        requires = "spec",
+        help = "Read the input of the problem given by --spec from this file"
+    )]
+    input: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "SPEC",
+        requires = "input",
+        help = "The problem solved with --input, in the format yyyy.dd:variant (it is solved by \
+                default if no problems are specified)"
+    )]
+    spec: Option<Spec>,
+
+    #[clap(
+        long,
+        conflicts_with_all = &["input", "file"],
+        help = "Read the input from stdin and solve it as the only problem given, in the format \
+                yyyy.dd[:variant][/part] (the variant defaults to stdin)"
+    )]
+    stdin: bool,
+
+    #[clap(
+        long,
+        value_name = "PATH",
+        conflicts_with = "input",
+        help = "Read the input from this file and solve it as the only problem given, in the \
+                format yyyy.dd[:variant][/part] (the variant defaults to file)"
+    )]
+    file: Option<PathBuf>,
+
     #[clap(
         short,
         long,
@@ -47,178 +135,761 @@ pub struct Cmd {
     )]
     color: ColorChoice,
 
+    #[clap(
+        short,
+        long,
+        default_value = "terminal",
+        help = "Controls the output format (terminal, json, jsonl)"
+    )]
+    format: OutputFormat,
+
     #[clap(short, long)]
     quiet: bool,
+
+    #[clap(
+        long,
+        conflicts_with = "record",
+        help = "Compare the answers with the expected answers database and exit with an error on \
+                any mismatch"
+    )]
+    check: bool,
+
+    #[clap(long, help = "Save the answers to the expected answers database")]
+    record: bool,
+
+    #[clap(
+        long,
+        default_value = "answers.json",
+        help = "The expected answers database used by --check and --record"
+    )]
+    answers: PathBuf,
+
+    #[clap(
+        long,
+        value_name = "NAME",
+        help = "Save the timings of each problem as a named baseline"
+    )]
+    save_baseline: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "NAME",
+        help = "Compare the timings of each problem with a named baseline"
+    )]
+    baseline: Option<String>,
+
+    #[clap(
+        long,
+        default_value = "5",
+        help = "Flag changes slower than the baseline by more than this percentage as regressions"
+    )]
+    regression_threshold: f64,
+
+    #[clap(
+        long,
+        default_value = "target/baselines",
+        help = "The directory where baselines are stored"
+    )]
+    baseline_dir: PathBuf,
+
+    #[clap(
+        long,
+        value_name = "SECS",
+        value_parser = parse_duration_s,
+        help = "Give up on a problem if its solver runs for longer than this many seconds, \
+                counting all the runs of a benchmark (the solver keeps running in the background \
+                until the program exits, which can slow down the problems solved after it)"
+    )]
+    timeout: Option<Duration>,
 }
 
 impl Cmd {
-    pub fn exec(&self, default_inputs: &impl input::Source) -> anyhow::Result<()> {
-        let specs = self.find_specs(default_inputs);
+    pub fn exec(&self, default_inputs: &(impl input::Source + Sync)) -> anyhow::Result<()> {
+        let ad_hoc = self.ad_hoc_spec()?;
+        let inputs = input::chain(
+            self.ad_hoc_input(ad_hoc.as_ref())?,
+            input::chain(self.input_overrides()?, default_inputs),
+        );
+        let specs = self.find_specs(&inputs, ad_hoc);
         let solvers = Solver::get_map();
-        for spec in specs {
-            let solver = solvers
-                .get(&spec.id)
-                .ok_or_else(|| anyhow!("No solver found for problem {}", spec.id))?;
-            let input = default_inputs.get(spec).unwrap();
-            self.run_solver(spec, solver, &input)?;
+        let mut store = if self.check || self.record {
+            AnswerStore::load(&self.answers)?
+        } else {
+            AnswerStore::default()
+        };
+        let mut report = CheckReport::default();
+        let baseline = match &self.baseline {
+            Some(name) => Some(Baseline::load(&self.baseline_dir, name)?),
+            None => None,
+        };
+        let mut comparison = Comparison::new(self.regression_threshold / 100.0);
+        let mut new_baseline = match &self.save_baseline {
+            Some(name) => Baseline::load_or_default(&self.baseline_dir, name)?,
+            None => Baseline::default(),
+        };
+        let mut mismatches = 0;
+        let mut on_solved = |spec: &Spec, part: Option<Part>, recording: Recording| {
+            mismatches += recording.mismatches;
+            if self.check {
+                report.add(spec, part, store.get(spec), &recording.answers);
+            }
+            if self.record {
+                store.record(spec, &recording.answers);
+            }
+            if let Some(baseline) = &baseline {
+                comparison.add(spec, baseline, &recording.stats);
+            }
+            if self.save_baseline.is_some() {
+                new_baseline.record(spec, &recording.stats);
+            }
+        };
+
+        let mut writer = self.writer();
+        let jobs = self.jobs();
+        if jobs <= 1 {
+            for (spec, part) in specs {
+                let mut recorder = Recorder::new(writer.as_mut());
+                self.solve_spec(spec, part, &solvers, &inputs, &mut recorder)?;
+                on_solved(spec, part, recorder.into_recording());
+            }
+        } else {
+            self.exec_parallel(
+                &specs,
+                &solvers,
+                &inputs,
+                jobs,
+                writer.as_mut(),
+                &mut on_solved,
+            )?;
+        }
+        writer.finish()?;
+
+        if self.record {
+            store.save(&self.answers)?;
+        }
+        if let Some(name) = &self.save_baseline {
+            new_baseline.save(&self.baseline_dir, name)?;
+        }
+        if let Some(name) = &self.baseline {
+            comparison.print(name, &mut self.report_stream())?;
+        }
+        if self.check {
+            report.print(&mut self.report_stream())?;
+            let failures = report.failures();
+            if failures > 0 {
+                bail!("{failures} answer(s) didn't match the expected answers");
+            }
+        }
+        if mismatches > 0 {
+            bail!("{mismatches} answer(s) didn't match the expected answers given by the inputs");
         }
         Ok(())
     }
 
-    fn find_specs<'a>(&self, default_inputs: &'a impl input::Source) -> Vec<&'a Spec> {
-        let mut specs = FxHashSet::default();
-        let mut useful = vec![false; self.problems_filters.len()];
-        for spec in default_inputs.keys() {
-            for (i, pf) in self.problems_filters.iter().enumerate() {
-                if pf.matches_spec(spec) {
-                    useful[i] = true;
-                    specs.insert(spec);
-                }
+    /// The inputs given on the command line, which take precedence over the
+    /// embedded inputs.
+    fn input_overrides(&self) -> anyhow::Result<input::FSSource> {
+        let mut source = input::FSSource::default();
+        for dir in &self.inputs_dirs {
+            source.add_dir(dir)?;
+        }
+        if let (Some(spec), Some(file)) = (&self.spec, &self.input) {
+            source.add_path_with_spec(spec.clone(), file.clone());
+        }
+        Ok(source)
+    }
+
+    /// The problem whose input is given by --stdin or --file, along with the
+    /// part to be solved.
+    fn ad_hoc_spec(&self) -> anyhow::Result<Option<(Spec, Option<Part>)>> {
+        let default_variant = match (self.stdin, &self.file) {
+            (true, _) => "stdin",
+            (false, Some(_)) => "file",
+            (false, None) => return Ok(None),
+        };
+        let [filter] = self.problems_filters.as_slice() else {
+            bail!("--stdin and --file expect a single problem, in the format yyyy.dd[:variant]");
+        };
+        let variant = match filter.variant() {
+            Some(variant) if variant.contains('*') => {
+                bail!("expected a single variant, got {}", filter.raw)
             }
+            Some(variant) => variant,
+            None => default_variant,
+        };
+        let spec = Spec {
+            id: filter.problem_id()?,
+            variant: variant.to_owned(),
+        };
+        Ok(Some((spec, filter.part())))
+    }
+
+    /// The input given by --stdin or --file, which takes precedence over all
+    /// the other inputs.
+    fn ad_hoc_input(
+        &self,
+        ad_hoc: Option<&(Spec, Option<Part>)>,
+    ) -> anyhow::Result<input::MemorySource<'static>> {
+        let mut source = input::new_memory();
+        let Some((spec, _)) = ad_hoc else {
+            return Ok(source);
+        };
+        let content = if let Some(path) = &self.file {
+            // Read through `FSSource` to support compressed and encrypted files
+            input::from_file(spec.clone(), path)
+                .get(spec)
+                .with_context(|| format!("failed to read the input from {}", path.display()))?
+                .to_string()
+        } else {
+            let mut content = String::new();
+            io::stdin()
+                .read_to_string(&mut content)
+                .context("failed to read the input from stdin")?;
+            content
+        };
+        source.add_string(spec.clone(), content);
+        Ok(source)
+    }
+
+    fn problems_filters(&self, ad_hoc: Option<(Spec, Option<Part>)>) -> Vec<ProblemFilter> {
+        if let Some((spec, part)) = ad_hoc {
+            let mut filter = ProblemFilter::from(&spec);
+            filter.part = part;
+            return vec![filter];
+        }
+        match &self.spec {
+            Some(spec) if self.problems_filters.is_empty() => vec![spec.into()],
+            _ => self.problems_filters.clone(),
         }
-        let not_useful = useful
+    }
+
+    /// Returns the specs selected by the filters, along with the part to be
+    /// solved (or `None` to solve both parts).
+    fn find_specs<'a>(
+        &self,
+        inputs: &'a impl input::Source,
+        ad_hoc: Option<(Spec, Option<Part>)>,
+    ) -> Vec<(&'a Spec, Option<Part>)> {
+        let index = input::Index::new(inputs);
+        let (exclusions, inclusions): (Vec<_>, Vec<_>) = self
+            .problems_filters(ad_hoc)
             .into_iter()
-            .enumerate()
-            .filter(|(_i, useful)| !useful)
-            .map(|(i, _useful)| &self.problems_filters[i].raw)
-            .collect_vec();
+            .partition(|pf| pf.exclude);
+        let mut specs: BTreeMap<&Spec, Option<Part>> = BTreeMap::new();
+        if inclusions.is_empty() && !exclusions.is_empty() {
+            // Only exclusions, so exclude them from all the problems
+            specs.extend(index.iter().map(|spec| (spec, None)));
+        }
+
+        let mut not_useful = Vec::new();
+        for pf in inclusions {
+            let mut matched = false;
+            for spec in pf.matching(&index) {
+                matched = true;
+                specs
+                    .entry(spec)
+                    .and_modify(|part| {
+                        if *part != pf.part() {
+                            *part = None;
+                        }
+                    })
+                    .or_insert(pf.part());
+            }
+            if !matched {
+                not_useful.push(pf.raw);
+            }
+        }
+        for pf in exclusions {
+            let mut matched = false;
+            for spec in pf.matching(&index) {
+                matched = true;
+                match (pf.part(), specs.get(spec)) {
+                    // Keep the other part
+                    (Some(Part::One), Some(None)) => specs.insert(spec, Some(Part::Two)),
+                    (Some(Part::Two), Some(None)) => specs.insert(spec, Some(Part::One)),
+                    (Some(excluded), Some(Some(part))) if excluded != *part => None,
+                    _ => specs.remove(spec),
+                };
+            }
+            if !matched {
+                not_useful.push(pf.raw);
+            }
+        }
         if !not_useful.is_empty() {
-            println!(
+            eprintln!(
                 "Warning: the following filters didn't match any problems (or there were no \
                  inputs available):\n  {}",
                 not_useful.into_iter().join("\n  ")
             );
         }
-        specs.into_iter().sorted_unstable().collect()
+        specs.into_iter().collect()
+    }
+
+    /// Solves the given problems in `jobs` worker threads, writing the output
+    /// in the same order as `specs`.
+    fn exec_parallel(
+        &self,
+        specs: &[(&Spec, Option<Part>)],
+        solvers: &FxHashMap<ProblemId, &'static Solver>,
+        inputs: &(impl input::Source + Sync),
+        jobs: usize,
+        writer: &mut dyn SolutionWriter,
+        on_solved: &mut dyn FnMut(&Spec, Option<Part>, Recording),
+    ) -> anyhow::Result<()> {
+        let (senders, receivers): (Vec<_>, Vec<_>) = specs.iter().map(|_| mpsc::channel()).unzip();
+        let queue = Mutex::new(specs.iter().zip(senders));
+        // Set once the run has failed, so that no more problems are started
+        let aborted = AtomicBool::new(false);
+
+        thread::scope(|scope| {
+            for _ in 0..jobs.min(specs.len()) {
+                scope.spawn(|| loop {
+                    if aborted.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let Some((&(spec, part), sender)) = queue.lock().unwrap().next() else {
+                        break;
+                    };
+                    let mut writer = ChannelWriter::new(sender);
+                    if let Err(e) = self.solve_spec(spec, part, solvers, inputs, &mut writer) {
+                        aborted.store(true, Ordering::Relaxed);
+                        // If the receiver is gone, the main thread has already failed
+                        writer.send(Event::Abort(e)).ok();
+                    }
+                });
+            }
+
+            // Each channel is closed once its problem has been solved
+            let replay = || {
+                for (&(spec, part), receiver) in specs.iter().zip(receivers) {
+                    let mut recorder = Recorder::new(&mut *writer);
+                    for event in receiver {
+                        event.replay(&mut recorder)?;
+                    }
+                    on_solved(spec, part, recorder.into_recording());
+                }
+                Ok(())
+            };
+            let result = replay();
+            if result.is_err() {
+                aborted.store(true, Ordering::Relaxed);
+            }
+            result
+        })
+    }
+
+    fn jobs(&self) -> usize {
+        let jobs = if self.jobs == 0 {
+            thread::available_parallelism().map_or(1, NonZeroUsize::get)
+        } else {
+            self.jobs
+        };
+        if jobs > 1 && self.is_bench() {
+            // Running problems concurrently would skew the measurements
+            eprintln!("Warning: ignoring --jobs, since benchmarks are always run serially");
+            return 1;
+        }
+        jobs
+    }
+
+    fn is_bench(&self) -> bool {
+        self.run_settings().is_bench()
     }
 
-    fn run_solver(&self, spec: &Spec, solver: &Solver, input: &str) -> anyhow::Result<()> {
-        let mut writer = terminal_writer::TerminalWriter {
-            color_choice: self.color.into(),
+    fn run_settings(&self) -> RunSettings {
+        RunSettings {
+            min_runs: self.min_runs,
+            min_duration: self.min_duration_s,
+            warmup: self.warmup,
             quiet: self.quiet,
+        }
+    }
+
+    fn solve_spec(
+        &self,
+        spec: &Spec,
+        part: Option<Part>,
+        solvers: &FxHashMap<ProblemId, &'static Solver>,
+        inputs: &impl input::Source,
+        writer: &mut dyn SolutionWriter,
+    ) -> anyhow::Result<()> {
+        let solver = solvers
+            .get(&spec.id)
+            .copied()
+            .ok_or_else(|| anyhow!("No solver found for problem {}", spec.id))?;
+        let content = match inputs.get(spec) {
+            Ok(content) => content,
+            // Report inputs that can't be decrypted like solver errors, so
+            // that the other problems are still solved
+            Err(e @ (input::Error::MissingKey { .. } | input::Error::DecryptionFailed { .. })) => {
+                writer.write_heading(spec)?;
+                writer.write_error(&SolverError::SolverError(e.into()))?;
+                writer.write_footer(Duration::ZERO)?;
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
         };
+        let input = input::Input::parse(&content)?;
+        self.run_solver(spec, part, solver, writer, &input)
+    }
+
+    /// The stream used for reports printed after all problems have been solved.
+    fn report_stream(&self) -> StandardStream {
+        // Keep stdout clean when it's used for structured output
+        match self.format {
+            OutputFormat::Terminal => StandardStream::stdout(self.color.into()),
+            OutputFormat::Json | OutputFormat::JsonLines => {
+                StandardStream::stderr(self.color.into())
+            }
+        }
+    }
+
+    fn writer(&self) -> Box<dyn SolutionWriter> {
+        match self.format {
+            OutputFormat::Terminal => Box::new(TerminalWriter {
+                color_choice: self.color.into(),
+                quiet: self.quiet,
+                detailed_stats: self.detailed_stats,
+            }),
+            OutputFormat::Json => Box::new(JsonWriter::new(false)),
+            OutputFormat::JsonLines => Box::new(JsonWriter::new(true)),
+        }
+    }
+
+    fn run_solver(
+        &self,
+        spec: &Spec,
+        part: Option<Part>,
+        solver: &'static Solver,
+        writer: &mut dyn SolutionWriter,
+        input: &input::Input<'_>,
+    ) -> anyhow::Result<()> {
+        let settings = self.run_settings();
+        match self.timeout {
+            Some(timeout) => {
+                settings.run_solver_with_timeout(spec, part, solver, writer, input, timeout)
+            }
+            None => settings.run_solver(spec, part, solver, writer, input),
+        }
+    }
+}
 
-        if self.min_runs <= 1 && self.min_duration_s == Duration::ZERO {
-            Self::run_solver_once(spec, solver, &mut writer, input)?;
+/// The options that control how each solver is run, kept apart from [`Cmd`]
+/// so that they can be sent to the thread running a solver.
+#[derive(Debug, Clone, Copy)]
+struct RunSettings {
+    min_runs: u64,
+    min_duration: Duration,
+    warmup: usize,
+    quiet: bool,
+}
+
+impl RunSettings {
+    fn is_bench(self) -> bool {
+        self.min_runs > 1 || self.min_duration > Duration::ZERO || self.warmup > 0
+    }
+
+    fn run_solver(
+        self,
+        spec: &Spec,
+        part: Option<Part>,
+        solver: &Solver,
+        writer: &mut dyn SolutionWriter,
+        input: &input::Input<'_>,
+    ) -> anyhow::Result<()> {
+        if self.is_bench() {
+            self.run_solver_bench(spec, part, solver, writer, input)
         } else {
-            self.run_solver_bench(spec, solver, &mut writer, input)?;
+            Self::run_solver_once(spec, part, solver, writer, input)
         }
+    }
 
-        Ok(())
+    /// Runs the solver in a separate thread, giving up on it if it doesn't
+    /// finish within `timeout`. Since threads can't be killed, a solver that
+    /// times out keeps running in the background until the program exits.
+    fn run_solver_with_timeout(
+        self,
+        spec: &Spec,
+        part: Option<Part>,
+        solver: &'static Solver,
+        writer: &mut dyn SolutionWriter,
+        input: &input::Input<'_>,
+        timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        let owned_spec = spec.clone();
+        let text = input.text.to_owned();
+        let params = input.params.clone();
+        let expected = input.expected.clone();
+        thread::spawn(move || {
+            let input = input::Input {
+                text: &text,
+                params,
+                expected,
+            };
+            let mut writer = ChannelWriter::new(sender);
+            if let Err(e) = self.run_solver(&owned_spec, part, solver, &mut writer, &input) {
+                // If the receiver is gone, the solver has timed out
+                writer.send(Event::Abort(e)).ok();
+            }
+        });
+
+        let deadline = Instant::now() + timeout;
+        let mut has_heading = false;
+        loop {
+            match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(event) => {
+                    // The footer is the last event, so don't wait for the thread to exit
+                    let is_footer = matches!(event, Event::Footer(_));
+                    has_heading |= matches!(event, Event::Heading(_));
+                    event.replay(writer)?;
+                    if is_footer {
+                        return Ok(());
+                    }
+                }
+                Err(e) => {
+                    // Complete the output of the problem, which may not even
+                    // have a heading yet
+                    if !has_heading {
+                        writer.write_heading(spec)?;
+                    }
+                    let err = match e {
+                        RecvTimeoutError::Timeout => SolverError::Timeout(timeout),
+                        RecvTimeoutError::Disconnected => SolverError::SolverError(anyhow!(
+                            "the solver thread exited without finishing"
+                        )),
+                    };
+                    writer.write_error(&err)?;
+                    writer.write_footer(Duration::ZERO)?;
+                    return Ok(());
+                }
+            }
+        }
     }
 
     fn run_solver_once(
         spec: &Spec,
+        part: Option<Part>,
         solver: &Solver,
-        writer: &mut terminal_writer::TerminalWriter,
-        input: &str,
+        writer: &mut dyn SolutionWriter,
+        input: &input::Input<'_>,
     ) -> anyhow::Result<()> {
-        let mut out = ProblemOutput::start(spec, writer)?;
-        if let Err(e) = solver.solve(input, &mut out) {
-            writer.error(&e)?;
-        }
+        solver.run_with(spec, input, part, writer)?;
         Ok(())
     }
 
     fn run_solver_bench(
-        &self,
+        self,
         spec: &Spec,
+        part: Option<Part>,
         solver: &Solver,
-        writer: &mut terminal_writer::TerminalWriter,
-        input: &str,
+        writer: &mut dyn SolutionWriter,
+        input: &input::Input<'_>,
     ) -> anyhow::Result<()> {
-        let mut out = ProblemOutput::start(spec, writer)?;
+        let mut out = ProblemOutput::start(spec, writer)?
+            .with_params(input.params.clone())
+            .with_expected(input.expected.clone())
+            .with_part(part);
         out.hide_solutions();
+        out.set_warmup_runs(self.warmup);
 
+        // Warm-up runs don't count towards the minimum number of runs
+        let min_runs = self.min_runs.max(1) + self.warmup as u64;
         let mut err = None;
-        for i in 0.. {
+        for i in 1.. {
             out.reset_timer();
-            if let Err(e) = solver.solve(input, &mut out) {
+            if let Err(e) = solver.solve(input.text, &mut out) {
                 err = Some(e);
                 break;
             }
             let total_time = out.total_time();
-            if i >= self.min_runs.saturating_sub(1) && total_time >= self.min_duration_s {
+            if i >= min_runs && total_time >= self.min_duration {
                 break;
             }
         }
+        let total_time = out.total_time();
+        let dropped_time = out.dropped_time();
         if let Some(err) = err {
-            writer.error(&err)?;
+            out.writer().write_error(&err)?;
+            out.finish()?;
             return Ok(());
         }
         out.show_solutions()?;
 
         // Warn about dropped time
-        let total_time = out.total_time();
-        let dropped_time = out.dropped_time();
         let dropped_percent = dropped_time.as_secs_f64()
             / (dropped_time.as_secs_f64() + total_time.as_secs_f64())
             * 100.0;
         if !self.quiet && dropped_percent > MAX_DROPPED_PERCENT * 100.0 {
-            writer.warn(&anyhow!(
+            out.writer().write_warning(&format_args!(
                 "Warning: wasted {dropped_percent:.1}% of execution time \
                  (dropped={dropped_time:.1?}, useful={total_time:.1?})"
             ))?;
         }
+        out.finish()?;
 
         Ok(())
     }
 }
 
+/// Selects problems to be solved, in the format
+/// `[!]yyyy[.dd][:variant][/part]`.
+///
+/// Years and days can be `*`, or lists of numbers and ranges (e.g.
+/// `2022.1,3,10-15`), and variants can contain `*` wildcards (e.g. `test*`).
+/// Filters starting with `!` exclude the problems they match.
 #[derive(Clone, Debug)]
-struct ProblemFilter {
+pub(crate) struct ProblemFilter {
     pub raw: String,
 
-    year: Option<u32>,
-    day: Option<u32>,
+    /// Whether the matching problems are excluded instead of included.
+    pub exclude: bool,
+    years: Numbers,
+    days: Numbers,
     variant: Option<String>,
+    part: Option<Part>,
 }
 
 impl FromStr for ProblemFilter {
     type Err = anyhow::Error;
 
-    fn from_str(mut s: &str) -> Result<Self, Self::Err> {
-        let mut pf = ProblemFilter {
-            raw: s.to_owned(),
-            year: None,
-            day: None,
-            variant: None,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = s.to_owned();
+        let (exclude, s) = match s.strip_prefix('!') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let (s, part) = match s.rsplit_once('/') {
+            Some((s, "*")) => (s, None),
+            Some((s, "1")) => (s, Some(Part::One)),
+            Some((s, "2")) => (s, Some(Part::Two)),
+            Some((_, part)) => bail!("invalid part: {part} (must be 1, 2 or *)"),
+            None => (s, None),
         };
         let (raw_date, variant) = match s.split_once(':') {
             Some((raw_date, variant)) => (raw_date, Some(variant.to_owned())),
             None => (s, None),
         };
-        pf.variant = variant;
+        let (years, days) = match raw_date.split_once('.') {
+            Some((years, days)) => (years, Numbers::parse(days, "day")?),
+            None => (raw_date, Numbers::default()),
+        };
 
-        if let Some((year, day)) = raw_date.split_once('.') {
-            if day != "*" {
-                pf.day = Some(day.parse().with_context(|| format!("invalid day: {day}"))?);
-            }
-            s = year;
-        } else {
-            s = raw_date;
-        }
-        if s != "*" {
-            pf.year = Some(s.parse().with_context(|| format!("invalid year: {s}"))?);
-        }
+        Ok(ProblemFilter {
+            raw,
+            exclude,
+            years: Numbers::parse(years, "year")?,
+            days,
+            variant,
+            part,
+        })
+    }
+}
 
-        Ok(pf)
+impl From<&Spec> for ProblemFilter {
+    fn from(spec: &Spec) -> Self {
+        ProblemFilter {
+            raw: spec.to_string(),
+            exclude: false,
+            years: Numbers::single(spec.id.year),
+            days: Numbers::single(spec.id.day),
+            variant: Some(spec.variant.clone()),
+            part: None,
+        }
     }
 }
 
 impl ProblemFilter {
-    pub fn matches_spec(&self, spec: &Spec) -> bool {
-        self.day.map_or(true, |day| day == spec.id.day)
-            && self.year.map_or(true, |year| year == spec.id.year)
-            && self
-                .variant
-                .as_ref()
-                .map_or(true, |variant| variant == &spec.variant || variant == "*")
+    /// Returns the specs in `index` selected by this filter.
+    pub fn matching<'s, 'a>(
+        &'s self,
+        index: &'s input::Index<'a>,
+    ) -> impl Iterator<Item = &'a Spec> + 's {
+        index
+            .matching(
+                self.years.single_value(),
+                self.days.single_value(),
+                self.variant.as_deref(),
+            )
+            .filter(|spec| self.years.contains(spec.id.year) && self.days.contains(spec.id.day))
+    }
+
+    pub fn variant(&self) -> Option<&str> {
+        self.variant.as_deref()
+    }
+
+    /// Returns the part selected by this filter, or `None` if it selects
+    /// both parts.
+    pub fn part(&self) -> Option<Part> {
+        self.part
+    }
+
+    /// Returns the single problem selected by this filter, failing if the
+    /// year or the day is missing, a wildcard or a list.
+    pub fn problem_id(&self) -> anyhow::Result<ProblemId> {
+        match (
+            self.exclude,
+            self.years.single_value(),
+            self.days.single_value(),
+        ) {
+            (false, Some(year), Some(day)) => Ok(ProblemId { year, day }),
+            _ => bail!("expected a single problem (yyyy.dd), got {}", self.raw),
+        }
+    }
+}
+
+/// A set of years or days given as a comma separated list of numbers and
+/// ranges (e.g. `1,3,10-15`), or `*` (or nothing) to match any number.
+#[derive(Clone, Debug, Default)]
+struct Numbers(Option<Vec<RangeInclusive<u32>>>);
+
+impl Numbers {
+    fn parse(s: &str, what: &str) -> anyhow::Result<Self> {
+        if s == "*" {
+            return Ok(Self(None));
+        }
+        let parse_number = |n: &str| {
+            n.trim()
+                .parse::<u32>()
+                .with_context(|| format!("invalid {what}: {n}"))
+        };
+        let ranges = s
+            .split(',')
+            .map(|item| match item.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (parse_number(start)?, parse_number(end)?);
+                    if start > end {
+                        bail!("invalid {what} range: {item}");
+                    }
+                    Ok(start..=end)
+                }
+                None => parse_number(item).map(|n| n..=n),
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self(Some(ranges)))
+    }
+
+    fn single(n: u32) -> Self {
+        Self(Some(vec![n..=n]))
+    }
+
+    fn contains(&self, n: u32) -> bool {
+        self.0
+            .as_ref()
+            .is_none_or(|ranges| ranges.iter().any(|range| range.contains(&n)))
+    }
+
+    /// Returns the only number in the set, if it has a single number.
+    fn single_value(&self) -> Option<u32> {
+        match self.0.as_deref() {
+            Some([range]) if range.start() == range.end() => Some(*range.start()),
+            _ => None,
+        }
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-enum ColorChoice {
+pub(crate) enum ColorChoice {
     Always,
     Auto,
     Never,
@@ -240,6 +911,29 @@ impl FromStr for ColorChoice {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+enum OutputFormat {
+    Terminal,
+    Json,
+    JsonLines,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "terminal" => Self::Terminal,
+            "json" => Self::Json,
+            "jsonl" => Self::JsonLines,
+            _ => anyhow::bail!(
+                "invalid output format \"{}\" (must be terminal, json or jsonl)",
+                s.trim()
+            ),
+        })
+    }
+}
+
 impl From<ColorChoice> for termcolor::ColorChoice {
     fn from(val: ColorChoice) -> Self {
         match val {
@@ -250,6 +944,195 @@ impl From<ColorChoice> for termcolor::ColorChoice {
     }
 }
 
-fn parse_duration_s(raw: &str) -> Result<Duration, <f64 as FromStr>::Err> {
+pub(crate) fn parse_duration_s(raw: &str) -> Result<Duration, <f64 as FromStr>::Err> {
     Ok(Duration::from_secs_f64(raw.parse()?))
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::AocApp;
+
+    const SPECS: [&str; 6] = [
+        "2021.1:real",
+        "2022.1:real",
+        "2022.1:test",
+        "2022.2:real",
+        "2022.2:test1",
+        "2022.2:test2",
+    ];
+
+    fn filter(s: &str) -> ProblemFilter {
+        s.parse().unwrap()
+    }
+
+    fn source() -> input::MemorySource<'static> {
+        let mut source = input::new_memory();
+        for spec in SPECS {
+            source.add_str(spec.parse().unwrap(), "");
+        }
+        source
+    }
+
+    /// Returns the specs (and parts) selected by `run` with the given filters.
+    fn find_specs(filters: &[&str]) -> Vec<(String, Option<Part>)> {
+        let args = ["aoc", "run"].into_iter().chain(filters.iter().copied());
+        let AocApp::Run(cmd) = AocApp::try_parse_from(args).unwrap() else {
+            unreachable!();
+        };
+        cmd.find_specs(&source(), None)
+            .into_iter()
+            .map(|(spec, part)| (spec.to_string(), part))
+            .collect()
+    }
+
+    fn specs(specs: &[&str], part: Option<Part>) -> Vec<(String, Option<Part>)> {
+        specs
+            .iter()
+            .map(|spec| ((*spec).to_owned(), part))
+            .collect()
+    }
+
+    #[test]
+    fn parse_numbers() {
+        let days = Numbers::parse("1,3,10-15", "day").unwrap();
+        for day in [1, 3, 10, 12, 15] {
+            assert!(days.contains(day), "{day}");
+        }
+        for day in [2, 9, 16] {
+            assert!(!days.contains(day), "{day}");
+        }
+        assert_eq!(days.single_value(), None);
+
+        assert_eq!(Numbers::parse("7", "day").unwrap().single_value(), Some(7));
+        assert_eq!(
+            Numbers::parse("7-7", "day").unwrap().single_value(),
+            Some(7)
+        );
+        let any = Numbers::parse("*", "day").unwrap();
+        assert!(any.contains(1) && any.contains(25));
+        assert_eq!(any.single_value(), None);
+    }
+
+    #[test]
+    fn parse_invalid_numbers() {
+        for (raw, message) in [
+            ("5-3", "invalid day range: 5-3"),
+            ("x", "invalid day: x"),
+            ("1,", "invalid day: "),
+            ("1-", "invalid day: "),
+        ] {
+            let err = Numbers::parse(raw, "day").unwrap_err();
+            assert_eq!(err.to_string(), message, "{raw}");
+        }
+    }
+
+    #[test]
+    fn parse_filters() {
+        let pf = filter("2022");
+        assert!(!pf.exclude);
+        assert!(pf.years.contains(2022) && !pf.years.contains(2021));
+        assert!(pf.days.contains(1) && pf.days.contains(25));
+        assert_eq!((pf.variant(), pf.part()), (None, None));
+
+        let pf = filter("!2021,2022.1-3:test*/2");
+        assert!(pf.exclude);
+        assert!(pf.years.contains(2021) && pf.years.contains(2022));
+        assert!(pf.days.contains(3) && !pf.days.contains(4));
+        assert_eq!((pf.variant(), pf.part()), (Some("test*"), Some(Part::Two)));
+
+        assert_eq!(filter("2022.1/*").part(), None);
+        assert_eq!(filter("*.1/1").part(), Some(Part::One));
+        assert!(filter("*").years.contains(2015));
+
+        for raw in ["2022.1/3", "2022.5-3", "2022.x", "x", "!"] {
+            assert!(raw.parse::<ProblemFilter>().is_err(), "{raw}");
+        }
+    }
+
+    #[test]
+    fn filter_problem_id() {
+        assert_eq!(
+            filter("2022.05:test/1").problem_id().unwrap(),
+            ProblemId { year: 2022, day: 5 }
+        );
+        for raw in ["2022", "2022.1-2", "2022.1,2", "*.1", "!2022.1"] {
+            assert!(filter(raw).problem_id().is_err(), "{raw}");
+        }
+    }
+
+    #[test]
+    fn filter_matching() {
+        let source = source();
+        let index = input::Index::new(&source);
+        let matching = |raw: &str| {
+            filter(raw)
+                .matching(&index)
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            matching("2022.2"),
+            ["2022.2:real", "2022.2:test1", "2022.2:test2"]
+        );
+        assert_eq!(
+            matching("*.1-2:test*"),
+            ["2022.1:test", "2022.2:test1", "2022.2:test2"]
+        );
+        assert_eq!(matching("2021,2022.1:real"), ["2021.1:real", "2022.1:real"]);
+        assert!(matching("2022.3-25").is_empty());
+    }
+
+    #[test]
+    fn find_specs_with_exclusions() {
+        assert_eq!(
+            find_specs(&["2022", "!2022.2:test*"]),
+            specs(&["2022.1:real", "2022.1:test", "2022.2:real"], None)
+        );
+        // Only exclusions exclude from all the problems
+        assert_eq!(
+            find_specs(&["!2022.2", "!*.*:test"]),
+            specs(&["2021.1:real", "2022.1:real"], None)
+        );
+        assert!(find_specs(&["!*"]).is_empty());
+    }
+
+    #[test]
+    fn find_specs_with_parts() {
+        assert_eq!(
+            find_specs(&["2022.1/2"]),
+            specs(&["2022.1:real", "2022.1:test"], Some(Part::Two))
+        );
+        // Selecting both parts separately selects both parts
+        assert_eq!(
+            find_specs(&["2022.1:real/1", "2022.1:real/2"]),
+            specs(&["2022.1:real"], None)
+        );
+        // Excluding a part keeps the other one
+        assert_eq!(
+            find_specs(&["2022.1", "!2022.1:test/1"]),
+            [
+                ("2022.1:real".to_owned(), None),
+                ("2022.1:test".to_owned(), Some(Part::Two))
+            ]
+        );
+        assert_eq!(
+            find_specs(&["2022.1:real/2", "!2022.1/1"]),
+            specs(&["2022.1:real"], Some(Part::Two))
+        );
+        assert!(find_specs(&["2022.1:real/2", "!2022.1/2"]).is_empty());
+        assert_eq!(
+            find_specs(&["!2022/1"]),
+            [
+                ("2021.1:real".to_owned(), None),
+                ("2022.1:real".to_owned(), Some(Part::Two)),
+                ("2022.1:test".to_owned(), Some(Part::Two)),
+                ("2022.2:real".to_owned(), Some(Part::Two)),
+                ("2022.2:test1".to_owned(), Some(Part::Two)),
+                ("2022.2:test2".to_owned(), Some(Part::Two)),
+            ]
+        );
+    }
+}
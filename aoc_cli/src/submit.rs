@@ -0,0 +1,416 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::Path,
+    sync::LazyLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context};
+use aoc::{
+    input::{self, Spec},
+    Part, ProblemId, Solver,
+};
+use clap::Args;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use termcolor::ColorChoice;
+
+use crate::{
+    answers::Recorder,
+    client::{Client, ClientArgs},
+    run::ProblemFilter,
+    TerminalWriter,
+};
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    #[clap(
+        name = "problem",
+        help = "The problem to submit, in the format yyyy.dd"
+    )]
+    problem: ProblemFilter,
+
+    #[clap(value_parser = parse_part, help = "The part to submit (1 or 2)")]
+    part: Part,
+
+    #[clap(
+        long,
+        help = "Only print the answer that would be submitted, without submitting it"
+    )]
+    dry_run: bool,
+
+    #[clap(flatten)]
+    client: ClientArgs,
+}
+
+impl Cmd {
+    pub fn exec(&self, default_inputs: &impl input::Source) -> anyhow::Result<()> {
+        let id = self.problem.problem_id()?;
+        let spec = Spec {
+            id,
+            variant: "real".to_owned(),
+        };
+        if matches!(self.problem.variant(), Some(variant) if variant != spec.variant) {
+            bail!("only the real input can be submitted");
+        }
+        let answer = solve(&spec, self.part, default_inputs)?;
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let history_path = self.client.state_dir.join("submissions.json");
+        let mut history = History::load(&history_path)?;
+        if let Some(reason) = history.rejection_reason(id, self.part, &answer) {
+            bail!("not submitting {answer:?}: {reason}");
+        }
+
+        let client = Client::new(&self.client)?;
+        let level = self.part.to_string();
+        let page = client.post_form(
+            &format!("/{}/day/{}/answer", id.year, id.day),
+            &[("level", &level), ("answer", &answer)],
+        )?;
+        let (outcome, message) = parse_response(&page);
+        history.record(id, self.part, answer, outcome)?;
+        history.save(&history_path)?;
+
+        println!("{message}");
+        match outcome {
+            Outcome::Correct => Ok(()),
+            _ => bail!("the answer wasn't accepted ({outcome})"),
+        }
+    }
+}
+
+/// Runs the solver for `spec`, printing its output, and returns the answer to
+/// `part`.
+fn solve(spec: &Spec, part: Part, inputs: &impl input::Source) -> anyhow::Result<String> {
+    let solvers = Solver::get_map();
+    let solver = solvers
+        .get(&spec.id)
+        .ok_or_else(|| anyhow!("No solver found for problem {}", spec.id))?;
+    let content = inputs.get(spec)?;
+    let input = input::Input::parse(&content)?;
+
+    let mut recorder = Recorder::new(TerminalWriter {
+        color_choice: if atty::is(atty::Stream::Stdout) {
+            ColorChoice::Auto
+        } else {
+            ColorChoice::Never
+        },
+        quiet: true,
+        detailed_stats: false,
+    });
+    solver.run_with(spec, &input, Some(part), &mut recorder)?;
+
+    recorder
+        .into_recording()
+        .answers
+        .get(part)
+        .map(str::to_owned)
+        .ok_or_else(|| anyhow!("the solver didn't produce an answer for part {part}"))
+}
+
+fn parse_part(raw: &str) -> anyhow::Result<Part> {
+    match raw.trim() {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => bail!("invalid part \"{}\" (must be 1 or 2)", raw.trim()),
+    }
+}
+
+/// The server's verdict on a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Outcome {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// The answer wasn't checked because another answer was submitted too
+    /// recently.
+    TooSoon,
+    /// The part was already solved or isn't unlocked yet.
+    WrongLevel,
+    Unknown,
+}
+
+impl Outcome {
+    fn is_wrong(self) -> bool {
+        matches!(self, Self::Incorrect | Self::TooHigh | Self::TooLow)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Outcome::Correct => "correct",
+            Outcome::Incorrect => "incorrect",
+            Outcome::TooHigh => "too high",
+            Outcome::TooLow => "too low",
+            Outcome::TooSoon => "submitted too soon",
+            Outcome::WrongLevel => "wrong level",
+            Outcome::Unknown => "unknown response",
+        })
+    }
+}
+
+/// Parses the page returned after submitting an answer, returning the outcome
+/// and the message shown by the server (as plain text).
+fn parse_response(page: &str) -> (Outcome, String) {
+    static ARTICLE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap());
+    static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+    static SPACE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
+
+    let article = ARTICLE_RE
+        .captures(page)
+        .map_or(page, |c| c.get(1).unwrap().as_str());
+    let message = TAG_RE.replace_all(article, "");
+    let message = SPACE_RE.replace_all(&message, " ").trim().to_owned();
+
+    let outcome = if message.contains("That's the right answer") {
+        Outcome::Correct
+    } else if message.contains("not the right answer") {
+        if message.contains("too high") {
+            Outcome::TooHigh
+        } else if message.contains("too low") {
+            Outcome::TooLow
+        } else {
+            Outcome::Incorrect
+        }
+    } else if message.contains("answer too recently") {
+        Outcome::TooSoon
+    } else if message.contains("solving the right level") {
+        Outcome::WrongLevel
+    } else {
+        Outcome::Unknown
+    };
+    (outcome, message)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Attempt {
+    part: usize,
+    answer: String,
+    outcome: Outcome,
+    /// Seconds since the unix epoch.
+    time: u64,
+}
+
+/// Every answer submitted so far, keyed by problem (`yyyy.dd`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct History {
+    attempts: BTreeMap<String, Vec<Attempt>>,
+}
+
+impl History {
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read submissions from {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("invalid submissions file {}", path.display()))
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create directory {}", dir.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("failed to write submissions to {}", path.display()))
+    }
+
+    fn attempts(&self, id: ProblemId, part: Part) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .get(&key(id))
+            .into_iter()
+            .flatten()
+            .filter(move |attempt| attempt.part == part.to_index() + 1)
+    }
+
+    /// Checks whether submitting `answer` is pointless given the previous
+    /// attempts, returning the reason if it is.
+    fn rejection_reason(&self, id: ProblemId, part: Part, answer: &str) -> Option<String> {
+        let number = answer.parse::<i128>().ok();
+        for attempt in self.attempts(id, part) {
+            if attempt.outcome == Outcome::Correct {
+                return Some(format!("part {part} was already solved"));
+            }
+            if attempt.outcome.is_wrong() && attempt.answer == answer {
+                return Some(format!("it was already submitted ({})", attempt.outcome));
+            }
+            // Too high/too low hints also rule out other answers
+            let Some((number, previous)) = number.zip(attempt.answer.parse::<i128>().ok()) else {
+                continue;
+            };
+            match attempt.outcome {
+                Outcome::TooHigh if number >= previous => {
+                    return Some(format!("{previous} was already too high"));
+                }
+                Outcome::TooLow if number <= previous => {
+                    return Some(format!("{previous} was already too low"));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn record(
+        &mut self,
+        id: ProblemId,
+        part: Part,
+        answer: String,
+        outcome: Outcome,
+    ) -> anyhow::Result<()> {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        self.attempts.entry(key(id)).or_default().push(Attempt {
+            part: part.to_index() + 1,
+            answer,
+            outcome,
+            time,
+        });
+        Ok(())
+    }
+}
+
+fn key(id: ProblemId) -> String {
+    format!("{}.{:02}", id.year, id.day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: ProblemId = ProblemId { year: 2022, day: 5 };
+
+    /// Wraps `article` in the page returned by the server.
+    fn page(article: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en-us\">\n<head>\n<title>Day 5 - Advent of Code \
+             2022</title>\n</head><body>\n<header><h1 class=\"title-global\"><a \
+             href=\"/\">Advent of Code</a></h1></header>\n<main>\n{article}\n</main>\n</body>\n\
+             </html>"
+        )
+    }
+
+    fn history(attempts: &[(Part, &str, Outcome)]) -> History {
+        let mut history = History::default();
+        for &(part, answer, outcome) in attempts {
+            history
+                .record(ID, part, answer.to_owned(), outcome)
+                .unwrap();
+        }
+        history
+    }
+
+    #[test]
+    fn right_answer() {
+        let (outcome, message) = parse_response(&page(
+            "<article><p>That's the right answer!  You are <span class=\"day-success\">one gold \
+             star</span> closer to collecting enough star fruit. <a \
+             href=\"/2022/day/5#part2\">[Continue to Part Two]</a></p></article>",
+        ));
+        assert_eq!(outcome, Outcome::Correct);
+        assert_eq!(
+            message,
+            "That's the right answer! You are one gold star closer to collecting enough star \
+             fruit. [Continue to Part Two]"
+        );
+    }
+
+    #[test]
+    fn wrong_answers() {
+        let wrong = |hint: &str| {
+            parse_response(&page(&format!(
+                "<article><p>That's not the right answer{hint}.  If you're stuck, make sure \
+                 you're using the full input data; there are also some general tips on the <a \
+                 href=\"/2022/about\">about page</a>, or you can ask for hints on the <a \
+                 href=\"https://www.reddit.com/r/adventofcode/\" \
+                 target=\"_blank\">subreddit</a>.  Please wait one minute before trying again. \
+                 <a href=\"/2022/day/5\">[Return to Day 5]</a></p></article>"
+            )))
+            .0
+        };
+        assert_eq!(wrong(""), Outcome::Incorrect);
+        assert_eq!(wrong("; your answer is too high"), Outcome::TooHigh);
+        assert_eq!(wrong("; your answer is too low"), Outcome::TooLow);
+    }
+
+    #[test]
+    fn answer_submitted_too_soon() {
+        let (outcome, message) = parse_response(&page(
+            "<article><p>You gave an answer too recently; you have to wait after submitting an \
+             answer before trying again.  You have 39s left to wait. <a \
+             href=\"/2022/day/5\">[Return to Day 5]</a></p></article>",
+        ));
+        assert_eq!(outcome, Outcome::TooSoon);
+        assert!(message.contains("You have 39s left to wait."));
+    }
+
+    #[test]
+    fn already_solved() {
+        let (outcome, _) = parse_response(&page(
+            "<article><p>You don't seem to be solving the right level.  Did you already complete \
+             it? <a href=\"/2022/day/5\">[Return to Day 5]</a></p></article>",
+        ));
+        assert_eq!(outcome, Outcome::WrongLevel);
+    }
+
+    #[test]
+    fn unknown_response() {
+        let (outcome, message) = parse_response("Internal Server Error");
+        assert_eq!(outcome, Outcome::Unknown);
+        assert_eq!(message, "Internal Server Error");
+    }
+
+    #[test]
+    fn too_high_and_too_low_bound_the_answer() {
+        let history = history(&[
+            (Part::One, "100", Outcome::TooHigh),
+            (Part::One, "10", Outcome::TooLow),
+        ]);
+        assert_eq!(
+            history.rejection_reason(ID, Part::One, "150").unwrap(),
+            "100 was already too high"
+        );
+        assert_eq!(
+            history.rejection_reason(ID, Part::One, "101").unwrap(),
+            "100 was already too high"
+        );
+        assert_eq!(
+            history.rejection_reason(ID, Part::One, "5").unwrap(),
+            "10 was already too low"
+        );
+        assert_eq!(history.rejection_reason(ID, Part::One, "50"), None);
+        assert_eq!(history.rejection_reason(ID, Part::One, "99"), None);
+        // The bounds only apply to the same part
+        assert_eq!(history.rejection_reason(ID, Part::Two, "150"), None);
+    }
+
+    #[test]
+    fn repeated_and_solved_answers_are_rejected() {
+        let history = history(&[
+            (Part::One, "abc", Outcome::Incorrect),
+            (Part::One, "def", Outcome::TooSoon),
+            (Part::Two, "42", Outcome::Correct),
+        ]);
+        assert_eq!(
+            history.rejection_reason(ID, Part::One, "abc").unwrap(),
+            "it was already submitted (incorrect)"
+        );
+        // The answer wasn't checked, so it can be submitted again
+        assert_eq!(history.rejection_reason(ID, Part::One, "def"), None);
+        assert_eq!(
+            history.rejection_reason(ID, Part::Two, "43").unwrap(),
+            "part 2 was already solved"
+        );
+    }
+}
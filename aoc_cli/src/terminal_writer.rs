@@ -1,6 +1,7 @@
 use std::{fmt::Display, io::Write};
 
 use aoc::{input, stats::Stats, Part, SolutionWriter};
+use itertools::Itertools;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 use thousands::Separable;
 
@@ -14,6 +15,7 @@ enum OutputType {
 pub struct TerminalWriter {
     pub color_choice: ColorChoice,
     pub quiet: bool,
+    pub detailed_stats: bool,
 }
 
 impl TerminalWriter {
@@ -32,6 +34,25 @@ impl TerminalWriter {
         }
     }
 
+    fn write_detailed_stats(stdout: &mut StandardStream, stats: &Stats) -> aoc::Result<()> {
+        let Stats {
+            exec_time_min,
+            exec_time_p5,
+            exec_time_median,
+            exec_time_p95,
+            exec_time_max,
+            outliers,
+            ..
+        } = stats;
+        writeln!(
+            stdout,
+            "        (min {exec_time_min:.1?}, p5 {exec_time_p5:.1?}, median \
+             {exec_time_median:.1?}, p95 {exec_time_p95:.1?}, max {exec_time_max:.1?}, {outliers} \
+             outliers)"
+        )?;
+        Ok(())
+    }
+
     pub fn error(&self, err: &dyn std::fmt::Debug) -> aoc::Result<()> {
         let mut stdout = StandardStream::stdout(self.color_choice);
         stdout.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
@@ -89,7 +110,16 @@ impl SolutionWriter for TerminalWriter {
                 exec_time_total,
                 exec_time_mean,
                 exec_time_std,
+                memory,
+                ..
             } = stats;
+            let memory = memory.map_or_else(String::new, |memory| {
+                format!(
+                    ", peak {} in {} allocations",
+                    format_bytes(memory.peak_bytes),
+                    memory.allocations.separate_with_underscores()
+                )
+            });
             if let Some(exec_time_std) = exec_time_std {
                 let exec_count = exec_count.separate_with_underscores();
                 #[allow(clippy::cast_precision_loss)]
@@ -98,14 +128,94 @@ impl SolutionWriter for TerminalWriter {
                 writeln!(
                     stdout,
                     "(finished in {exec_time_mean:.1?} ± {exec_time_std:.1?} \
-                     (±{std_percent:.1}%), {exec_time_total:.1?}/{exec_count} runs)"
+                     (±{std_percent:.1}%), {exec_time_total:.1?}/{exec_count} runs{memory})"
                 )?;
             } else {
-                writeln!(stdout, "(finished in {exec_time_mean:.1?})")?;
+                writeln!(stdout, "(finished in {exec_time_mean:.1?}{memory})")?;
+            }
+            if self.detailed_stats && *exec_count > 1 {
+                Self::write_detailed_stats(&mut stdout, stats)?;
             }
         }
 
         stdout.reset()?;
         Ok(())
     }
+
+    fn write_phases(
+        &mut self,
+        phases: &[(&str, Stats)],
+        parts: [Option<Stats>; 2],
+    ) -> aoc::Result<()> {
+        if self.quiet {
+            return Ok(());
+        }
+
+        let part_names = ["part 1", "part 2"];
+        let breakdown = phases
+            .iter()
+            .map(|&(name, stats)| (name, stats))
+            .chain(
+                part_names
+                    .into_iter()
+                    .zip(parts)
+                    .filter_map(|(name, stats)| Some((name, stats?))),
+            )
+            .collect_vec();
+        let total_secs: f64 = breakdown
+            .iter()
+            .map(|(_, stats)| stats.exec_time_mean.as_secs_f64())
+            .sum();
+
+        let mut stdout = StandardStream::stdout(self.color_choice);
+        write!(stdout, "    [phases] ")?;
+        stdout.set_color(ColorSpec::new().set_dimmed(true))?;
+        let breakdown = breakdown
+            .into_iter()
+            .map(|(name, stats)| {
+                let percent = stats.exec_time_mean.as_secs_f64() / total_secs * 100.0;
+                format!("{name} {:.1?} ({percent:.1}%)", stats.exec_time_mean)
+            })
+            .join(", ");
+        writeln!(stdout, "{breakdown}")?;
+        stdout.reset()?;
+        Ok(())
+    }
+
+    fn write_error(&mut self, err: &aoc::SolverError) -> aoc::Result<()> {
+        self.error(err)
+    }
+
+    fn write_mismatch(&mut self, part: Part, expected: &str, _actual: &str) -> aoc::Result<()> {
+        let mut stdout = StandardStream::stdout(self.color_choice);
+        stdout.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+        write!(stdout, "    [part {part}] wrong answer, expected ")?;
+        Self::write(&mut stdout, &expected)?;
+        writeln!(stdout)?;
+        stdout.reset()?;
+        Ok(())
+    }
+
+    fn write_warning(&mut self, msg: &dyn Display) -> aoc::Result<()> {
+        self.warn(&format_args!("{msg}"))
+    }
+}
+
+/// Formats a number of bytes with a binary unit (e.g. `1.5 MiB`).
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{value:.1} {unit}")
 }
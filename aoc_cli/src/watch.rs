@@ -0,0 +1,257 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+use aoc::{input::Spec, stats::Stats, Part, ProblemId, SolutionWriter};
+use clap::Args;
+use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+
+use crate::{
+    json_writer::Record,
+    run::{parse_duration_s, ColorChoice, ProblemFilter},
+    TerminalWriter,
+};
+
+#[derive(Debug, Args)]
+pub struct Cmd {
+    #[clap(
+        name = "problem",
+        help = "The problems to run, in the format [!]yyyy[.dd][:variant][/part] (defaults to the \
+                most recently edited solution)"
+    )]
+    problem: Option<ProblemFilter>,
+
+    #[clap(long, help = "Build and run in release mode")]
+    release: bool,
+
+    #[clap(
+        long,
+        default_value = "src",
+        help = "The directory containing the year modules"
+    )]
+    src_dir: PathBuf,
+
+    #[clap(
+        long = "watch",
+        value_name = "PATH",
+        default_values = &["src", "inputs", "aoc", "aoc_cli", "build.rs", "Cargo.toml"],
+        help = "The files and directories to watch for changes"
+    )]
+    watch_paths: Vec<PathBuf>,
+
+    #[clap(
+        long,
+        value_parser = parse_duration_s,
+        default_value = "0.5",
+        help = "How often to check for changes, in seconds"
+    )]
+    interval_s: Duration,
+
+    #[clap(
+        short,
+        long,
+        default_value = "auto",
+        help = "Controls colored output (always, auto, never)"
+    )]
+    color: ColorChoice,
+}
+
+/// The answers of a run, keyed by problem and part number.
+type Answers = BTreeMap<(Spec, usize), String>;
+
+impl Cmd {
+    pub fn exec(&self) -> anyhow::Result<()> {
+        let mut previous = Answers::new();
+        let mut last_change = None;
+        loop {
+            let change = Some(self.last_change());
+            if change != last_change {
+                // Wait for editors to finish writing
+                thread::sleep(self.interval_s);
+                last_change = Some(self.last_change());
+                match self.run(&previous) {
+                    Ok(answers) => previous = answers,
+                    // Keep watching, the next change may fix it
+                    Err(e) => eprintln!("Error: {e:#}"),
+                }
+                println!("Watching for changes...");
+            }
+            thread::sleep(self.interval_s);
+        }
+    }
+
+    /// Builds and runs the selected problems, printing how their answers
+    /// changed since the `previous` run. Returns the new answers, or the
+    /// previous ones if the build or a solver failed.
+    fn run(&self, previous: &Answers) -> anyhow::Result<Answers> {
+        let filter = match &self.problem {
+            Some(problem) => Some(problem.raw.clone()),
+            None => latest_solution(&self.src_dir)?,
+        };
+        let Some(filter) = filter else {
+            eprintln!("No solutions found in {}", self.src_dir.display());
+            return Ok(previous.clone());
+        };
+
+        let mut cargo = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+        cargo.args(["run", "--quiet"]);
+        if self.release {
+            cargo.arg("--release");
+        }
+        cargo.args(["--", "run", &filter, "--format", "jsonl"]);
+        let output = cargo
+            .stderr(Stdio::inherit())
+            .output()
+            .context("failed to run cargo")?;
+
+        let mut writer = TerminalWriter {
+            color_choice: self.color.into(),
+            quiet: false,
+            detailed_stats: false,
+        };
+        let mut answers = Answers::new();
+        let mut current = None;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let record: Record = serde_json::from_str(line)
+                .with_context(|| format!("invalid output from the solvers: {line}"))?;
+            let spec = Spec {
+                id: ProblemId {
+                    year: record.year,
+                    day: record.day,
+                },
+                variant: record.variant,
+            };
+            if current.as_ref() != Some(&spec) {
+                writer.write_heading(&spec)?;
+                current = Some(spec.clone());
+            }
+            if let Some(error) = &record.error {
+                writer.error(&format_args!("{error}"))?;
+            }
+            let (Some(index), Some(solution)) = (record.part, &record.solution) else {
+                continue;
+            };
+            let part = if index == 1 { Part::One } else { Part::Two };
+            let stats = record.stats.as_ref().map(Stats::from).unwrap_or_default();
+            writer.write_solution(part, &stats, solution)?;
+            if let Some(expected) = &record.expected {
+                writer.write_mismatch(part, expected, solution)?;
+            }
+            if !previous.is_empty() {
+                print_change(self.color, previous.get(&(spec.clone(), index)), solution)?;
+            }
+            answers.insert((spec, index), solution.clone());
+        }
+
+        if !output.status.success() {
+            eprintln!("The solvers exited with {}", output.status);
+            return Ok(previous.clone());
+        }
+        Ok(answers)
+    }
+
+    /// Returns the number of watched files and the modification time of the
+    /// most recently modified one, which change whenever a file is added,
+    /// removed or modified.
+    fn last_change(&self) -> (usize, Option<SystemTime>) {
+        let mut count = 0;
+        let mut last = None;
+        for path in &self.watch_paths {
+            visit_files(path, &mut |path| {
+                count += 1;
+                let modified = path.metadata().and_then(|m| m.modified()).ok();
+                last = last.max(modified);
+            });
+        }
+        (count, last)
+    }
+}
+
+fn print_change(
+    color: ColorChoice,
+    previous: Option<&String>,
+    solution: &str,
+) -> anyhow::Result<()> {
+    let mut stdout = StandardStream::stdout(color.into());
+    match previous {
+        Some(previous) if previous == solution => return Ok(()),
+        Some(previous) => {
+            stdout.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+            if previous.contains('\n') {
+                writeln!(stdout, "        (changed)")?;
+            } else {
+                writeln!(stdout, "        (changed, was {previous})")?;
+            }
+        }
+        None => {
+            stdout.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)))?;
+            writeln!(stdout, "        (new)")?;
+        }
+    }
+    stdout.reset()?;
+    Ok(())
+}
+
+/// Finds the most recently modified solution (`yyyy/dayDD.rs`), returning it
+/// as a filter in the format `yyyy.dd`.
+fn latest_solution(src_dir: &Path) -> anyhow::Result<Option<String>> {
+    let mut latest: Option<(SystemTime, String)> = None;
+    let entries = std::fs::read_dir(src_dir)
+        .with_context(|| format!("failed to read {}", src_dir.display()))?;
+    for entry in entries {
+        let year_dir = entry?.path();
+        let Some(year) = file_name(&year_dir).and_then(|name| name.strip_prefix("year")) else {
+            continue;
+        };
+        let Ok(year) = year.parse::<u32>() else {
+            continue;
+        };
+        let Ok(days) = std::fs::read_dir(&year_dir) else {
+            continue;
+        };
+        for entry in days {
+            let path = entry?.path();
+            let Some(day) = file_name(&path).and_then(|name| {
+                name.strip_prefix("day")?
+                    .strip_suffix(".rs")?
+                    .parse::<u32>()
+                    .ok()
+            }) else {
+                continue;
+            };
+            let modified = path.metadata()?.modified()?;
+            if latest.as_ref().is_none_or(|(time, _)| modified > *time) {
+                latest = Some((modified, format!("{year}.{day:02}")));
+            }
+        }
+    }
+    Ok(latest.map(|(_, filter)| filter))
+}
+
+fn file_name(path: &Path) -> Option<&str> {
+    path.file_name()?.to_str()
+}
+
+fn visit_files(path: &Path, f: &mut impl FnMut(&Path)) {
+    if path.is_file() {
+        f(path);
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            visit_files(&path, f);
+        } else {
+            f(&path);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Generates a test case for every input that has a solver, so that `cargo
+//! test` checks each `year/day/variant` separately (see `src/tests.rs`).
+
+use std::{
+    env,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const INPUTS_DIR: &str = "inputs";
+const SRC_DIR: &str = "src";
+
+fn main() {
+    println!("cargo:rerun-if-changed={INPUTS_DIR}");
+    println!("cargo:rerun-if-changed={SRC_DIR}");
+
+    let mut files = Vec::new();
+    find_files(Path::new(INPUTS_DIR), &mut files);
+
+    let mut specs: Vec<_> = files.iter().filter_map(|path| parse_spec(path)).collect();
+    specs.retain(|(year, day, _)| has_solver(*year, *day));
+    specs.sort_unstable();
+    specs.dedup();
+
+    let mut tests = String::new();
+    for (year, day, variant) in specs {
+        let name: String = variant
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        writeln!(
+            tests,
+            "solver_test!(year{year}_day{day:02}_{name}, {year}, {day}, {variant:?});"
+        )
+        .unwrap();
+    }
+
+    let out_path = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("solver_tests.rs");
+    fs::write(out_path, tests).unwrap();
+}
+
+fn find_files(path: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Parses paths such as `inputs/2022/day01_test.txt.gz` into the year, day and
+/// variant of the input (mirroring `aoc::input::parse_input_filename`).
+fn parse_spec(path: &Path) -> Option<(u32, u32, String)> {
+    let year = path.parent()?.file_name()?.to_str()?.parse().ok()?;
+    let mut name = path.file_name()?.to_str()?;
+    name = name.strip_suffix(".enc").unwrap_or(name);
+    name = name
+        .strip_suffix(".gz")
+        .or_else(|| name.strip_suffix(".zst"))
+        .unwrap_or(name);
+    let (day, variant) = name
+        .strip_suffix(".txt")?
+        .strip_prefix("day")?
+        .split_once('_')?;
+    if variant.contains('.') {
+        return None;
+    }
+    Some((year, day.parse().ok()?, variant.to_owned()))
+}
+
+fn has_solver(year: u32, day: u32) -> bool {
+    Path::new(SRC_DIR)
+        .join(format!("year{year}"))
+        .join(format!("day{day:02}.rs"))
+        .exists()
+}
@@ -25,6 +25,9 @@ pub mod year2020;
 pub mod year2021;
 pub mod year2022;
 
+#[cfg(test)]
+mod tests;
+
 fn main() -> anyhow::Result<()> {
     let app = aoc_cli::parse();
     let default_inputs = input::from_embedded::<EmbeddedInput>()?;
@@ -0,0 +1,89 @@
+//! Runs every solver on each of its inputs and compares the answers with the
+//! ones recorded in `answers.json` (and with the expected answers given by
+//! the inputs themselves). The test cases are generated by `build.rs`.
+
+use std::{panic, path::Path, thread};
+
+use aoc::{
+    input::{self, Source, Spec},
+    Part, ProblemId, Solver, SolverError,
+};
+use aoc_cli::AnswerStore;
+
+use crate::EmbeddedInput;
+
+fn check(year: u32, day: u32, variant: &str) {
+    let spec = Spec {
+        id: ProblemId { year, day },
+        variant: variant.to_owned(),
+    };
+    let solver = Solver::get_map()
+        .get(&spec.id)
+        .copied()
+        .unwrap_or_else(|| panic!("no solver registered for {}", spec.id));
+    let inputs = input::from_embedded::<EmbeddedInput>().unwrap();
+    let content = match inputs.get(&spec) {
+        Ok(content) => content,
+        Err(e @ input::Error::MissingKey { .. }) => {
+            eprintln!("skipping {spec}: {e}");
+            return;
+        }
+        Err(e) => panic!("failed to read the input of {spec}: {e}"),
+    };
+    let input = input::Input::parse(&content).unwrap();
+
+    let report = solver.run(&input);
+    match &report.error {
+        // Days created by `new` are stubs until they are solved
+        Some(SolverError::NotImplemented) => {
+            eprintln!("skipping {spec}: the solver isn't implemented");
+            return;
+        }
+        Some(e) => panic!("{spec}: {e:?}"),
+        None => {}
+    }
+    assert!(
+        report.mismatches.is_empty(),
+        "{spec}: the answers didn't match the ones given by the input: {:?}",
+        report.mismatches
+    );
+
+    let store =
+        AnswerStore::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("answers.json")).unwrap();
+    let Some(expected) = store.get(&spec) else {
+        return;
+    };
+    for part in [Part::One, Part::Two] {
+        if let Some(expected) = expected.get(part) {
+            assert_eq!(
+                report.answer(part),
+                Some(expected),
+                "{spec}: wrong answer for part {part}"
+            );
+        }
+    }
+}
+
+/// Some solvers recurse deeply, which needs more than the default stack size
+/// of test threads (especially in debug builds).
+fn run_with_stack(f: impl FnOnce() + Send + 'static) {
+    const STACK_SIZE: usize = 64 * 1024 * 1024;
+    let handle = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(f)
+        .unwrap();
+    if let Err(panic) = handle.join() {
+        panic::resume_unwind(panic);
+    }
+}
+
+macro_rules! solver_test {
+    ($name:ident, $year:expr, $day:expr, $variant:expr) => {
+        #[test]
+        fn $name() {
+            run_with_stack(|| check($year, $day, $variant));
+        }
+    };
+}
+
+include!(concat!(env!("OUT_DIR"), "/solver_tests.rs"));
@@ -25,9 +25,10 @@ pub fn solve(input: &str, out: &mut ProblemOutput<'_>) -> anyhow::Result<()> {
     sensors_beacons.sort_by_key(|s| (s.0.0, s.0.1));
     let sensors_beacons = sensors_beacons;
 
-    let is_real = out.variant() == "real";
+    // The examples use smaller values, given in their front matter
+    let target_y = out.param_or("target_y", 2_000_000_i64)?;
+    let max_xy = out.param_or("max_xy", 4_000_000_i64)? + 1;
 
-    let target_y = if is_real { 2_000_000 } else { 10 };
     let beacons_at_target_y = sensors_beacons
         .iter()
         .map(|(_, beacon)| beacon)
@@ -38,7 +39,6 @@ pub fn solve(input: &str, out: &mut ProblemOutput<'_>) -> anyhow::Result<()> {
     push_range_at_y(&mut blocked_coords, &sensors_beacons, target_y);
     out.set_part1(blocked_coords.len() - beacons_at_target_y);
 
-    let max_xy = if is_real { 4_000_000 } else { 20 } + 1;
     let xy_range = 0..max_xy;
     out.set_part2(
         xy_range
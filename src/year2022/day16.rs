@@ -9,11 +9,14 @@ aoc::register!(solve, 2022, 16);
 
 pub fn solve(input: &str, out: &mut ProblemOutput<'_>) -> anyhow::Result<()> {
     let mut valves = Valve::parse_valves(input, "AA".to_string())?;
+    out.phase("parse");
     bfs(&mut valves);
+    out.phase("bfs");
     remove_useless_valves(&mut valves);
+    out.phase("simplify");
 
-    out.set_part1(Optimizer::<1>::new(&valves, 30).optimize());
-    out.set_part2(Optimizer::<2>::new(&valves, 26).optimize());
+    out.part1_with(|| Optimizer::<1>::new(&valves, 30).optimize());
+    out.part2_with(|| Optimizer::<2>::new(&valves, 26).optimize());
 
     Ok(())
 }
@@ -14,7 +14,7 @@ const GEODE: usize = 3;
 pub fn solve(input: &str, out: &mut ProblemOutput<'_>) -> anyhow::Result<()> {
     let blueprints: Vec<Blueprint> = input.trim().lines().map(str::parse).try_collect()?;
 
-    out.set_part1(
+    out.part1_with(|| {
         blueprints
             .iter()
             .map(|bp| {
@@ -22,10 +22,10 @@ pub fn solve(input: &str, out: &mut ProblemOutput<'_>) -> anyhow::Result<()> {
                 opt.optimize(&Simulation::new(bp));
                 opt.max_geode * bp.id
             })
-            .sum::<u32>(),
-    );
+            .sum::<u32>()
+    });
 
-    out.set_part2(
+    out.part2_with(|| {
         blueprints
             .iter()
             .take(3)
@@ -34,8 +34,8 @@ pub fn solve(input: &str, out: &mut ProblemOutput<'_>) -> anyhow::Result<()> {
                 opt.optimize(&Simulation::new(bp));
                 opt.max_geode
             })
-            .product::<u32>(),
-    );
+            .product::<u32>()
+    });
 
     Ok(())
 }